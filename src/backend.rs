@@ -0,0 +1,35 @@
+//! Abstracts over the different player sources `run()` can follow: MPRIS
+//! over D-Bus (see `player::DbusBackend`) and MPD (see `mpd::MpdBackend`).
+//! [`Player`] lets either drive the same backend-agnostic event loop without
+//! it knowing which one is in use.
+
+use std::sync::mpsc::Sender;
+use std::thread;
+use std::time::Duration;
+
+use crate::events::{PlayerState, TimedEvent};
+
+/// Pushes `PlayerEvent`s onto a `TimedEvent` channel, and builds on-demand
+/// query handles for whichever session a `PlayerEvent::PlayerStarted` just
+/// announced.
+pub trait Player: Send {
+    /// Spawns whatever background thread(s) the backend needs to push
+    /// `PlayerEvent`s onto `sender` for the life of the process.
+    fn run_async(&self, sender: Sender<TimedEvent>) -> thread::JoinHandle<()>;
+
+    /// Builds a query handle for the session identified by
+    /// `player_owner_name` (the value carried by the `PlayerStarted` event
+    /// that just fired). Called once per session rather than kept across
+    /// the whole run, since the backend-specific handle behind it (a D-Bus
+    /// proxy, an MPD connection) may not outlive that session.
+    fn connect(&self, player_owner_name: &str) -> Result<Box<dyn PlayerQuery>, String>;
+}
+
+/// Answers on-demand state/position queries for a connected player session,
+/// for the cases where an event wakes the main loop but doesn't itself
+/// carry enough information (e.g. right after `PlayerStarted`, or to
+/// snapshot the exact position a pause happened at).
+pub trait PlayerQuery {
+    fn query_player_state(&self) -> Result<PlayerState, String>;
+    fn query_player_position(&self) -> Result<Duration, String>;
+}
@@ -0,0 +1,141 @@
+//! Typed client-side bindings for the `lrcshow-rs` D-Bus API.
+//!
+//! Every consumer of the daemon otherwise has to hand-roll `Message`/
+//! `MatchRule` parsing for the custom `ActiveLyricsChanged`/
+//! `ActiveLyricsSegmentChanged` signals. This module provides strongly
+//! typed bindings (mirroring the pattern used for `MediaPlayer2SeekedHappened`
+//! in `player.rs`) plus thin wrappers for the two `Lyrics` methods, so other
+//! Rust programs can integrate against the daemon in a few lines instead of
+//! copying match-rule boilerplate.
+
+use std::time::Duration;
+
+use dbus::arg::{self, AppendAll, ReadAll};
+use dbus::blocking::LocalConnection;
+use dbus::message::SignalArgs;
+use dbus::Message;
+
+const DAEMON_PATH: &str = "/com/github/nikola_kocic/lrcshow_rs/Daemon";
+const DAEMON_INTERFACE: &str = "com.github.nikola_kocic.lrcshow_rs.Daemon";
+const LYRICS_PATH: &str = "/com/github/nikola_kocic/lrcshow_rs/Lyrics";
+const LYRICS_INTERFACE: &str = "com.github.nikola_kocic.lrcshow_rs.Lyrics";
+
+/// Mirrors the `ActiveLyricsChanged` signal emitted whenever the loaded
+/// lyrics file changes. Carries no data; call
+/// [`LyricsProxy::get_current_lyrics`] to fetch the new lines.
+#[derive(Debug)]
+pub struct ActiveLyricsChanged;
+
+impl SignalArgs for ActiveLyricsChanged {
+    const NAME: &'static str = "ActiveLyricsChanged";
+    const INTERFACE: &'static str = DAEMON_INTERFACE;
+}
+
+impl AppendAll for ActiveLyricsChanged {
+    fn append(&self, _i: &mut arg::IterAppend) {}
+}
+
+impl ReadAll for ActiveLyricsChanged {
+    fn read(_i: &mut arg::Iter) -> Result<Self, arg::TypeMismatchError> {
+        Ok(Self)
+    }
+}
+
+/// Mirrors the `ActiveLyricsSegmentChanged` signal emitted whenever the
+/// currently highlighted lyrics segment changes. `line_index`, `char_from`
+/// and `char_to` are all `-1` when no segment is active.
+#[derive(Debug)]
+pub struct ActiveLyricsSegmentChanged {
+    pub line_index: i32,
+    pub char_from: i32,
+    pub char_to: i32,
+}
+
+impl SignalArgs for ActiveLyricsSegmentChanged {
+    const NAME: &'static str = "ActiveLyricsSegmentChanged";
+    const INTERFACE: &'static str = DAEMON_INTERFACE;
+}
+
+impl AppendAll for ActiveLyricsSegmentChanged {
+    fn append(&self, i: &mut arg::IterAppend) {
+        arg::RefArg::append(&(self.line_index, self.char_from, self.char_to), i);
+    }
+}
+
+impl ReadAll for ActiveLyricsSegmentChanged {
+    fn read(i: &mut arg::Iter) -> Result<Self, arg::TypeMismatchError> {
+        let (line_index, char_from, char_to): (i32, i32, i32) = i.read()?;
+        Ok(Self {
+            line_index,
+            char_from,
+            char_to,
+        })
+    }
+}
+
+/// Thin wrapper around the `Lyrics` object's `GetCurrentLyrics` and
+/// `GetCurrentLyricsPosition` methods.
+pub struct LyricsProxy<'a> {
+    proxy: dbus::blocking::Proxy<'a, &'a LocalConnection>,
+}
+
+impl<'a> LyricsProxy<'a> {
+    pub fn new(connection: &'a LocalConnection) -> Self {
+        LyricsProxy {
+            proxy: connection.with_proxy(
+                "com.github.nikola_kocic.lrcshow_rs",
+                LYRICS_PATH,
+                Duration::from_millis(5000),
+            ),
+        }
+    }
+
+    pub fn get_current_lyrics(&self) -> Result<Vec<String>, dbus::Error> {
+        let (lines,): (Vec<String>,) =
+            self.proxy
+                .method_call(LYRICS_INTERFACE, "GetCurrentLyrics", ())?;
+        Ok(lines)
+    }
+
+    pub fn get_current_lyrics_position(&self) -> Result<(i32, i32, i32), dbus::Error> {
+        let (position,): ((i32, i32, i32),) =
+            self.proxy
+                .method_call(LYRICS_INTERFACE, "GetCurrentLyricsPosition", ())?;
+        Ok(position)
+    }
+}
+
+/// Registers match rules for both daemon signals on `conn` and decodes
+/// incoming messages, forwarding lyrics-line changes and segment changes to
+/// `on_lyrics_changed` and `on_segment_changed` respectively.
+pub fn subscribe<FL, FS>(
+    conn: &LocalConnection,
+    mut on_lyrics_changed: FL,
+    mut on_segment_changed: FS,
+) -> Result<(), dbus::Error>
+where
+    FL: FnMut() + Send + 'static,
+    FS: FnMut(ActiveLyricsSegmentChanged) + Send + 'static,
+{
+    let proxy = conn.with_proxy(
+        "com.github.nikola_kocic.lrcshow_rs",
+        DAEMON_PATH,
+        Duration::from_millis(5000),
+    );
+
+    proxy.match_signal(
+        move |_: ActiveLyricsChanged, _: &LocalConnection, _: &Message| {
+            on_lyrics_changed();
+            true
+        },
+    )?;
+
+    proxy.match_signal(
+        move |e: ActiveLyricsSegmentChanged, _: &LocalConnection, _: &Message| {
+            on_segment_changed(e);
+            true
+        },
+    )?;
+
+    Ok(())
+}
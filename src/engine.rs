@@ -0,0 +1,83 @@
+//! A reusable, backend-agnostic lyric-sync engine: load an `.lrc` file, push
+//! player position updates, get back the segment that just became active.
+//! This is what `ffi` puts a C ABI on top of, for front-ends that want to
+//! embed the synchronization logic directly instead of driving it through
+//! [`crate::run`]'s D-Bus/MPD event loop.
+
+use std::path::Path;
+use std::time::Duration;
+
+use crate::lrc::{parse_lrc_file, Lyrics, LyricsTiming};
+
+/// One lyric line (or word, for enhanced/A2 files) becoming active at a
+/// given position.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ActiveSegment {
+    pub line_index: i32,
+    pub line_char_from_index: i32,
+    pub line_char_to_index: i32,
+}
+
+impl From<&LyricsTiming> for ActiveSegment {
+    fn from(timing: &LyricsTiming) -> Self {
+        ActiveSegment {
+            line_index: timing.line_index,
+            line_char_from_index: timing.line_char_from_index,
+            line_char_to_index: timing.line_char_to_index,
+        }
+    }
+}
+
+/// Owns a loaded `.lrc` file and the last position pushed into it, and
+/// reports when the active segment changes.
+pub struct SyncEngine {
+    lyrics: Option<Lyrics>,
+    current: Option<ActiveSegment>,
+}
+
+impl SyncEngine {
+    pub fn new() -> Self {
+        SyncEngine {
+            lyrics: None,
+            current: None,
+        }
+    }
+
+    /// Loads (or replaces) the lyrics that `push_position` is matched
+    /// against, resetting the active segment.
+    pub fn load_lrc_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), String> {
+        self.lyrics = Some(Lyrics::new(parse_lrc_file(path)?));
+        self.current = None;
+        Ok(())
+    }
+
+    /// The loaded file's lines, for a front-end to render; empty if nothing
+    /// is loaded yet.
+    pub fn lines(&self) -> &[String] {
+        self.lyrics
+            .as_ref()
+            .map_or(&[], |l| l.lines.as_slice())
+    }
+
+    /// Feeds a new player position. Returns the segment that became active,
+    /// or `None` if `position` falls in the same segment as the last call
+    /// (or nothing is loaded yet).
+    pub fn push_position(&mut self, position: Duration) -> Option<&ActiveSegment> {
+        let lyrics = self.lyrics.as_ref()?;
+        let timings = &lyrics.timings;
+        let idx = timings.partition_point(|t| t.time <= position);
+        let timing = idx.checked_sub(1).and_then(|i| timings.get(i))?;
+        let segment = ActiveSegment::from(timing);
+        if self.current.as_ref() == Some(&segment) {
+            return None;
+        }
+        self.current = Some(segment);
+        self.current.as_ref()
+    }
+}
+
+impl Default for SyncEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,19 +1,49 @@
 use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
+use url::Url;
+
 use crate::lrc::Lyrics;
-use crate::player::BusName;
 
-#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum PlaybackStatus {
     Playing,
     Paused,
     Stopped,
 }
 
+/// Where the track's audio lives, as reported by `xesam:url`. Most players
+/// report a local `file://` URL or bare absolute path, but streaming/podcast
+/// players (and `spotify:` URIs) report a remote location that has no
+/// corresponding `.lrc` file sitting next to it on disk.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MetadataLocation {
+    LocalFile(PathBuf),
+    Remote(Url),
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct Metadata {
-    pub file_path: PathBuf,
+    pub location: MetadataLocation,
+
+    /// `mpris:trackid`
+    pub track_id: Option<String>,
+
+    /// `xesam:title`
+    pub title: Option<String>,
+
+    /// `xesam:artist`
+    pub artist: Option<Vec<String>>,
+
+    /// `xesam:album`
+    pub album: Option<String>,
+
+    /// `mpris:length`
+    pub length: Option<Duration>,
+
+    /// `mpris:artUrl`
+    pub art_url: Option<Url>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -47,11 +77,18 @@ impl PlayerState {
 
 #[derive(Debug, PartialEq)]
 pub enum PlayerEvent {
-    PlayerStarted { player_owner_name: BusName },
+    /// `player_owner_name` identifies the backend-specific session that
+    /// started (a D-Bus unique name for the MPRIS backend, an opaque label
+    /// for others); it has no meaning outside the backend that produced it.
+    PlayerStarted { player_owner_name: String },
     PlayerShutDown,
     PlaybackStatusChange(PlaybackStatus),
     Seeked { position: Duration },
     MetadataChange(Option<Metadata>),
+    /// The player's `org.mpris.MediaPlayer2.TrackList` queue changed; carries
+    /// the metadata of every currently queued track, in order, so upcoming
+    /// `.lrc` files can be resolved ahead of playback reaching them.
+    TrackListChange(Vec<Metadata>),
     Unknown { key: String, value: String },
 }
 
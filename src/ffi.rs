@@ -0,0 +1,112 @@
+//! C ABI over [`crate::engine::SyncEngine`], for embedding the lyric-sync
+//! logic in non-Rust front-ends (GTK, Qt, mobile) instead of shelling out to
+//! the `lrcshow-rs` binary.
+//!
+//! None of these functions synchronize internally; a given engine handle
+//! must only be used from one thread at a time.
+
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_double, c_int};
+use std::time::Duration;
+
+use crate::engine::SyncEngine;
+
+/// Opaque handle returned by [`lrcshow_engine_create`]. Free with
+/// [`lrcshow_engine_destroy`].
+pub struct LrcshowEngine {
+    inner: SyncEngine,
+    callback: Option<extern "C" fn(line_index: c_int, char_from: c_int, char_to: c_int)>,
+}
+
+#[no_mangle]
+pub extern "C" fn lrcshow_engine_create() -> *mut LrcshowEngine {
+    Box::into_raw(Box::new(LrcshowEngine {
+        inner: SyncEngine::new(),
+        callback: None,
+    }))
+}
+
+/// Destroys an engine created by [`lrcshow_engine_create`]. Passing `NULL`
+/// is a no-op.
+///
+/// # Safety
+/// `engine` must either be `NULL` or a handle from [`lrcshow_engine_create`]
+/// that hasn't already been destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn lrcshow_engine_destroy(engine: *mut LrcshowEngine) {
+    if !engine.is_null() {
+        drop(Box::from_raw(engine));
+    }
+}
+
+/// Loads the `.lrc` file at `path` (a NUL-terminated, UTF-8 C string).
+/// Returns `0` on success, `-1` for a null/invalid handle or path, `-2` if
+/// the file couldn't be read or parsed.
+///
+/// # Safety
+/// `engine` must be a live handle from [`lrcshow_engine_create`]; `path`
+/// must be a valid, NUL-terminated, UTF-8 C string.
+#[no_mangle]
+pub unsafe extern "C" fn lrcshow_engine_load_lrc(
+    engine: *mut LrcshowEngine,
+    path: *const c_char,
+) -> c_int {
+    let Some(engine) = engine.as_mut() else {
+        return -1;
+    };
+    if path.is_null() {
+        return -1;
+    }
+    let Ok(path) = CStr::from_ptr(path).to_str() else {
+        return -1;
+    };
+    match engine.inner.load_lrc_file(path) {
+        Ok(()) => 0,
+        Err(e) => {
+            log::error!("lrcshow_engine_load_lrc: {e}");
+            -2
+        }
+    }
+}
+
+/// Registers the callback invoked from [`lrcshow_engine_push_position`]
+/// when the active segment changes. Pass `None`/a null function pointer to
+/// stop receiving callbacks.
+///
+/// # Safety
+/// `engine` must be a live handle from [`lrcshow_engine_create`].
+#[no_mangle]
+pub unsafe extern "C" fn lrcshow_engine_set_callback(
+    engine: *mut LrcshowEngine,
+    callback: Option<extern "C" fn(line_index: c_int, char_from: c_int, char_to: c_int)>,
+) {
+    if let Some(engine) = engine.as_mut() {
+        engine.callback = callback;
+    }
+}
+
+/// Pushes a new player position, in seconds, invoking the registered
+/// callback if it crosses into a new active segment. Negative positions are
+/// clamped to zero.
+///
+/// # Safety
+/// `engine` must be a live handle from [`lrcshow_engine_create`].
+#[no_mangle]
+pub unsafe extern "C" fn lrcshow_engine_push_position(
+    engine: *mut LrcshowEngine,
+    position_secs: c_double,
+) {
+    let Some(engine) = engine.as_mut() else {
+        return;
+    };
+    let position = Duration::from_secs_f64(position_secs.max(0.0));
+    if let Some(segment) = engine.inner.push_position(position) {
+        if let Some(callback) = engine.callback {
+            callback(
+                segment.line_index,
+                segment.line_char_from_index,
+                segment.line_char_to_index,
+            );
+        }
+    }
+}
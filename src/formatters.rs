@@ -10,6 +10,17 @@ pub fn format_duration(duration: &Duration) -> String {
     format!("{minutes:02}:{seconds:05.2}")
 }
 
+// Same as `format_duration`, but floors the minutes instead of rounding them.
+// `format_duration`'s rounding misformats times like 1:59.5 as "02:59.50";
+// round-tripping a tag through an LRC exporter needs the floored minutes so
+// the written tag parses back to the same duration.
+pub fn format_duration_floor(duration: &Duration) -> String {
+    let total_seconds = duration.as_secs_f32();
+    let minutes = (total_seconds / SECS_PER_MINUTE).floor();
+    let seconds = total_seconds % SECS_PER_MINUTE;
+    format!("{minutes:02}:{seconds:05.2}")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -38,4 +49,21 @@ mod tests {
     fn test_round_down() {
         assert_eq!(format_duration(&Duration::from_millis(164)), "00:00.16");
     }
+
+    #[test]
+    fn test_floor_does_not_round_minutes_up() {
+        // format_duration rounds this up to "02:59.50", which is wrong.
+        assert_eq!(
+            format_duration_floor(&Duration::from_millis(119500)),
+            "01:59.50"
+        );
+    }
+
+    #[test]
+    fn test_floor_regular() {
+        assert_eq!(
+            format_duration_floor(&Duration::from_millis(62550)),
+            "01:02.55"
+        );
+    }
 }
@@ -0,0 +1,124 @@
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+#[allow(unused_imports)]
+use log::{debug, trace};
+
+/// Tunables for the i3bar marquee: how many grapheme clusters are visible
+/// at once, how often the window advances, and what separates the end of
+/// the line from its wrapped repeat.
+pub struct I3barConfig {
+    pub window_width: usize,
+    pub tick: Duration,
+    pub separator: String,
+}
+
+impl Default for I3barConfig {
+    fn default() -> Self {
+        I3barConfig {
+            window_width: 40,
+            tick: Duration::from_millis(500),
+            separator: "   ".to_owned(),
+        }
+    }
+}
+
+struct State {
+    /// The active line, split into extended grapheme clusters so the
+    /// scrolling window never cuts a multi-byte/emoji glyph in half.
+    graphemes: Vec<String>,
+    offset: usize,
+}
+
+impl State {
+    fn empty() -> Self {
+        State {
+            graphemes: Vec::new(),
+            offset: 0,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct I3barOutput {
+    config: Arc<I3barConfig>,
+    state: Arc<Mutex<State>>,
+}
+
+impl I3barOutput {
+    pub fn on_current_line_changed(&self, line: Option<&str>) {
+        let graphemes = line
+            .unwrap_or("")
+            .graphemes(true)
+            .map(str::to_owned)
+            .collect();
+        {
+            let mut state = self.state.lock().unwrap();
+            state.graphemes = graphemes;
+            state.offset = 0;
+        }
+        self.render();
+    }
+
+    /// Prints one i3bar block array for the current window, then (if the
+    /// line is wider than the window) advances the window by one cluster
+    /// for next time.
+    fn render(&self) {
+        let separator: Vec<String> = self
+            .config
+            .separator
+            .graphemes(true)
+            .map(str::to_owned)
+            .collect();
+
+        let mut state = self.state.lock().unwrap();
+        let full_text = if state.graphemes.len() <= self.config.window_width {
+            state.graphemes.concat()
+        } else {
+            let wrap_len = state.graphemes.len() + separator.len();
+            let mut scrolling = state.graphemes.clone();
+            scrolling.extend_from_slice(&separator);
+            scrolling.extend(state.graphemes.iter().cloned());
+            let offset = state.offset % wrap_len;
+            scrolling[offset..offset + self.config.window_width].concat()
+        };
+
+        if state.graphemes.len() > self.config.window_width {
+            let wrap_len = state.graphemes.len() + separator.len();
+            state.offset = (state.offset + 1) % wrap_len;
+        }
+        drop(state);
+
+        let block = serde_json::json!([{ "full_text": full_text, "instance": "lrcshow" }]);
+        let mut stdout = io::stdout();
+        let _ = writeln!(stdout, "{block},");
+        let _ = stdout.flush();
+    }
+}
+
+/// Starts the i3bar output: prints the protocol header and the opening of
+/// the infinite block-array stream, then spawns the marquee tick thread
+/// that prints one block array per tick (re-printing the same window when
+/// the line fits, scrolling it otherwise).
+pub fn run_async(config: I3barConfig) -> I3barOutput {
+    println!("{{\"version\":1}}");
+    println!("[");
+
+    let output = I3barOutput {
+        config: Arc::new(config),
+        state: Arc::new(Mutex::new(State::empty())),
+    };
+
+    let tick_output = output.clone();
+    let tick = output.config.tick;
+    thread::spawn(move || loop {
+        thread::sleep(tick);
+        tick_output.render();
+    });
+
+    output
+}
@@ -0,0 +1,259 @@
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+#[allow(unused_imports)]
+use log::{debug, error, info, trace, warn};
+
+use serde::{Deserialize, Serialize};
+
+use crate::events::PlaybackStatus;
+
+/// How long to wait for a client's initial `IpcRequest` before falling back
+/// to the "subscribe to everything" default.
+const REQUEST_READ_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Bounds how long a broadcast will block on a single slow client. Without
+/// this, a client that stops reading fills its socket buffer and
+/// `write_all` blocks forever, freezing the whole `run()` loop behind it.
+const WRITE_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Which kinds of updates a connected client wants pushed to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IpcRequestKind {
+    CurrentLine,
+    NextLine,
+    PlaybackStatus,
+    Metadata,
+}
+
+/// Sent once by a client right after connecting to select which update
+/// kinds it wants. An empty (or absent/unreadable) request subscribes to
+/// everything.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct IpcRequest {
+    #[serde(default)]
+    pub kinds: Vec<IpcRequestKind>,
+}
+
+impl IpcRequest {
+    fn wants(&self, kind: IpcRequestKind) -> bool {
+        self.kinds.is_empty() || self.kinds.contains(&kind)
+    }
+}
+
+/// A single pushed update, length-prefixed and serde-encoded onto the
+/// client's stream.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum IpcUpdate {
+    CurrentLine {
+        line: Option<String>,
+        line_index: Option<i32>,
+        char_from: Option<i32>,
+        char_to: Option<i32>,
+    },
+    NextLine {
+        line: Option<String>,
+        line_index: Option<i32>,
+    },
+    PlaybackStatus {
+        status: PlaybackStatus,
+    },
+    Metadata {
+        title: Option<String>,
+        artist: Option<Vec<String>>,
+        album: Option<String>,
+    },
+}
+
+impl IpcUpdate {
+    fn kind(&self) -> IpcRequestKind {
+        match self {
+            IpcUpdate::CurrentLine { .. } => IpcRequestKind::CurrentLine,
+            IpcUpdate::NextLine { .. } => IpcRequestKind::NextLine,
+            IpcUpdate::PlaybackStatus { .. } => IpcRequestKind::PlaybackStatus,
+            IpcUpdate::Metadata { .. } => IpcRequestKind::Metadata,
+        }
+    }
+}
+
+struct Client {
+    stream: UnixStream,
+    request: IpcRequest,
+}
+
+fn write_framed(stream: &mut UnixStream, update: &IpcUpdate) -> std::io::Result<()> {
+    let body = serde_json::to_vec(update).expect("IpcUpdate is always serializable");
+    let len = u32::try_from(body.len()).expect("IpcUpdate body should never be this large");
+    stream.write_all(&len.to_le_bytes())?;
+    stream.write_all(&body)
+}
+
+/// Reads a single length-prefixed `IpcRequest` off a freshly-connected
+/// client. Any read/parse failure (including the client sending nothing
+/// before `REQUEST_READ_TIMEOUT` elapses) is treated as a request for
+/// everything, rather than dropping the connection.
+fn read_initial_request(stream: &mut UnixStream) -> IpcRequest {
+    stream.set_read_timeout(Some(REQUEST_READ_TIMEOUT)).ok();
+
+    let mut len_buf = [0u8; 4];
+    let request = if stream.read_exact(&mut len_buf).is_ok() {
+        let len = u32::from_le_bytes(len_buf) as usize;
+        let mut body = vec![0u8; len];
+        if stream.read_exact(&mut body).is_ok() {
+            serde_json::from_slice(&body).unwrap_or_default()
+        } else {
+            IpcRequest::default()
+        }
+    } else {
+        IpcRequest::default()
+    };
+
+    stream.set_read_timeout(None).ok();
+    request
+}
+
+#[derive(Clone)]
+pub struct IpcServer {
+    clients: Arc<Mutex<HashMap<usize, Client>>>,
+    next_client_id: Arc<Mutex<usize>>,
+}
+
+impl IpcServer {
+    /// Writes `update` to every subscribed client. Streams are cloned out
+    /// of `clients` and written to with the lock released, so new clients
+    /// can still register meanwhile. Writes happen one after another on the
+    /// caller's thread: a slow client delays the clients after it, and
+    /// `run()`'s caller, until its write times out (`WRITE_TIMEOUT`, set on
+    /// accept).
+    fn broadcast(&self, update: &IpcUpdate) {
+        let targets: Vec<(usize, UnixStream)> = {
+            let clients = self.clients.lock().unwrap();
+            clients
+                .iter()
+                .filter(|(_, client)| client.request.wants(update.kind()))
+                .filter_map(|(&id, client)| client.stream.try_clone().ok().map(|s| (id, s)))
+                .collect()
+        };
+
+        let mut dead = Vec::new();
+        for (id, mut stream) in targets {
+            if let Err(e) = write_framed(&mut stream, update) {
+                debug!("Dropping IPC client: {e}");
+                dead.push(id);
+            }
+        }
+
+        if !dead.is_empty() {
+            let mut clients = self.clients.lock().unwrap();
+            for id in dead {
+                clients.remove(&id);
+            }
+        }
+    }
+
+    pub fn on_current_line_changed(
+        &self,
+        line: Option<&str>,
+        line_index: Option<i32>,
+        char_from: Option<i32>,
+        char_to: Option<i32>,
+    ) {
+        self.broadcast(&IpcUpdate::CurrentLine {
+            line: line.map(str::to_owned),
+            line_index,
+            char_from,
+            char_to,
+        });
+    }
+
+    pub fn on_next_line_changed(&self, line: Option<&str>, line_index: Option<i32>) {
+        self.broadcast(&IpcUpdate::NextLine {
+            line: line.map(str::to_owned),
+            line_index,
+        });
+    }
+
+    pub fn on_playback_status_changed(&self, status: PlaybackStatus) {
+        self.broadcast(&IpcUpdate::PlaybackStatus { status });
+    }
+
+    pub fn on_metadata_changed(
+        &self,
+        title: Option<&str>,
+        artist: Option<&[String]>,
+        album: Option<&str>,
+    ) {
+        self.broadcast(&IpcUpdate::Metadata {
+            title: title.map(str::to_owned),
+            artist: artist.map(<[String]>::to_vec),
+            album: album.map(str::to_owned),
+        });
+    }
+}
+
+/// Starts the Unix-socket IPC server, removing any stale socket file left
+/// behind by a previous run. Clients connect, optionally send one
+/// length-prefixed `IpcRequest` selecting which update kinds they want, and
+/// then receive a length-prefixed serde-encoded `IpcUpdate` every time that
+/// kind of state changes.
+pub fn run_async(socket_path: PathBuf) -> (IpcServer, thread::JoinHandle<()>) {
+    let server = IpcServer {
+        clients: Arc::new(Mutex::new(HashMap::new())),
+        next_client_id: Arc::new(Mutex::new(0)),
+    };
+
+    let ret = server.clone();
+    let join_handle = thread::spawn(move || {
+        if socket_path.exists() {
+            std::fs::remove_file(&socket_path).ok();
+        }
+        let listener = match UnixListener::bind(&socket_path) {
+            Ok(l) => l,
+            Err(e) => {
+                error!("Failed to bind IPC socket {:?}: {e}", socket_path);
+                return;
+            }
+        };
+        info!("IPC server listening on {:?}", socket_path);
+
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(s) => s,
+                Err(e) => {
+                    warn!("Failed to accept IPC client: {e}");
+                    continue;
+                }
+            };
+
+            // The handshake read happens off the accept loop so a slow or
+            // silent client can't stall new connections.
+            let server = server.clone();
+            thread::spawn(move || {
+                let mut stream = stream;
+                stream.set_write_timeout(Some(WRITE_TIMEOUT)).ok();
+                let request = read_initial_request(&mut stream);
+
+                let client_id = {
+                    let mut next_client_id = server.next_client_id.lock().unwrap();
+                    let id = *next_client_id;
+                    *next_client_id += 1;
+                    id
+                };
+                server
+                    .clients
+                    .lock()
+                    .unwrap()
+                    .insert(client_id, Client { stream, request });
+            });
+        }
+    });
+
+    (ret, join_handle)
+}
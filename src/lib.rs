@@ -0,0 +1,382 @@
+//! The lyric-sync engine as a library: `run()` drives it against D-Bus/MPD
+//! for the `lrcshow-rs` binary, `engine` exposes a backend-agnostic handle
+//! for embedding it directly, and `ffi` puts a C ABI on top of that for
+//! non-Rust front-ends (GTK, Qt, mobile) that want to embed the
+//! synchronization logic instead of shelling out to the binary.
+
+pub mod backend;
+pub mod client;
+pub mod engine;
+pub mod events;
+pub mod ffi;
+pub mod formatters;
+pub mod i3bar;
+pub mod ipc;
+pub mod lrc;
+pub mod lrc_file_manager;
+pub mod mpd;
+pub mod net_sink;
+#[cfg(feature = "gstreamer-playback")]
+pub mod playback;
+pub mod player;
+pub mod server;
+pub mod sink;
+
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::mpsc::channel;
+use std::time::{Duration, Instant};
+
+#[allow(unused_imports)]
+use log::{debug, error, info, trace, warn};
+
+use crate::backend::{Player, PlayerQuery};
+use crate::events::{
+    Event, LyricsEvent, PlaybackStatus, PlayerEvent, PlayerState, PositionSnapshot, TimedEvent,
+};
+use crate::formatters::format_duration;
+use crate::lrc::{parse_lrc_file, Lyrics, LyricsTiming};
+use crate::lrc_file_manager::{get_lrc_filepath, LrcManager, WatcherKind};
+use crate::sink::Sink;
+
+pub static REFRESH_EVERY: Duration = Duration::from_millis(16);
+
+struct LrcTimedTextState<'a> {
+    current: Option<&'a LyricsTiming>,
+    next: Option<&'a LyricsTiming>,
+    iter: std::slice::Iter<'a, LyricsTiming>,
+}
+
+impl<'a> LrcTimedTextState<'a> {
+    fn new(lrc: &'a Lyrics, current_position: Duration) -> LrcTimedTextState<'a> {
+        let timings = &lrc.timings;
+        // `timings` is sorted ascending by `time` (see `Lyrics::new`), so
+        // find the split point in O(log n) rather than scanning from the
+        // front on every rebuild.
+        let idx = timings.partition_point(|t| t.time <= current_position);
+        let current = idx.checked_sub(1).and_then(|i| timings.get(i));
+        let next = timings.get(idx);
+        let iter = timings.get(idx + 1..).unwrap_or(&[]).iter();
+        debug!(
+            "LrcTimedTextState::new; current_position = {:?}, current = {:?}",
+            current_position, current
+        );
+        LrcTimedTextState {
+            current,
+            next,
+            iter,
+        }
+    }
+
+    fn on_position_advanced(&mut self, current_position: Duration) -> Option<&'a LyricsTiming> {
+        if let Some(timed_text) = self.next {
+            let subtract = std::cmp::min(REFRESH_EVERY / 2, timed_text.time);
+            if current_position >= timed_text.time - subtract {
+                self.current = Some(timed_text);
+                self.next = self.iter.next();
+                debug!(
+                    "Matched lyrics line at time {}, player time {}",
+                    format_duration(&timed_text.time),
+                    format_duration(&current_position)
+                );
+                return Some(timed_text);
+            }
+        }
+        None
+    }
+}
+
+fn line_text<'a>(lyrics: &'a Option<Lyrics>, timing: &LyricsTiming) -> Option<&'a str> {
+    lyrics
+        .as_ref()
+        .and_then(|l| l.lines.get(timing.line_index as usize))
+        .map(String::as_str)
+}
+
+/// Parses and caches `path`'s `.lrc` ahead of playback reaching it, so a
+/// queued track's lyrics are ready the moment it starts instead of being
+/// parsed on demand. A no-op if already cached or the file doesn't exist yet.
+fn prefetch_lrc(cache: &mut HashMap<PathBuf, Lyrics>, path: PathBuf) {
+    if cache.contains_key(&path) || !path.is_file() {
+        return;
+    }
+    match parse_lrc_file(&path) {
+        Ok(lrc_file) => {
+            debug!("Pre-resolved lyrics for queued track: {:?}", path);
+            cache.insert(path, Lyrics::new(lrc_file));
+        }
+        Err(e) => error!("Parsing queued lrc file {:?} failed: {}", path, e),
+    }
+}
+
+pub fn run(
+    backend: Box<dyn Player>,
+    lrc_filepath: &Option<PathBuf>,
+    ipc_socket: PathBuf,
+    i3bar_config: Option<i3bar::I3barConfig>,
+    listen_addr: Option<String>,
+    watcher_kind: WatcherKind,
+    #[cfg(feature = "gstreamer-playback")] use_embedded_playback: bool,
+) -> Option<()> {
+    // Uncapped by default; pass Some(rate) here to throttle
+    // ActiveLyricsSegmentChanged emission for word/char-level timings.
+    let (server, _server_join_handle) = server::run_async(None);
+    let (ipc_server, _ipc_join_handle) = ipc::run_async(ipc_socket);
+    let i3bar_output = i3bar_config.map(i3bar::run_async);
+
+    // `server` (D-Bus) is always present, matching today's default output;
+    // `--listen ADDR` adds the TCP sink alongside it. New transports plug in
+    // the same way, without `run()` needing to change.
+    let mut sinks: Vec<Box<dyn Sink>> = vec![Box::new(server)];
+    if let Some(addr) = listen_addr {
+        let (net_sink, _net_sink_join_handle) = net_sink::run_async(addr);
+        sinks.push(Box::new(net_sink));
+    }
+
+    let (sender, receiver) = channel::<TimedEvent>();
+
+    #[cfg(feature = "gstreamer-playback")]
+    if use_embedded_playback {
+        crate::playback::run_async(sender.clone());
+    }
+
+    backend.run_async(sender.clone());
+
+    let lrc_manager = LrcManager::new(sender, watcher_kind);
+    let lrc_manager_sender = lrc_manager.clone_sender();
+    if lrc_filepath.is_some() {
+        LrcManager::change_watched_path(lrc_filepath.clone(), &lrc_manager_sender);
+    }
+    lrc_manager.run_async();
+
+    let mut player_query: Option<Box<dyn PlayerQuery>> = None;
+    let mut lrc_state: Option<LrcTimedTextState> = None;
+    let mut player_state: Option<PlayerState> = None;
+    let mut lyrics: Option<Lyrics> = None;
+
+    // Lyrics for tracks queued ahead of the current one (from
+    // `PlayerEvent::TrackListChange`), pre-parsed as soon as the queue is
+    // known so switching to one of them doesn't have to wait on file I/O.
+    let mut upcoming_lyrics: HashMap<PathBuf, Lyrics> = HashMap::new();
+
+    loop {
+        let mut received_events = false;
+        match receiver.recv_timeout(REFRESH_EVERY) {
+            Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {}
+            Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => return None,
+            Ok(timed_event) => {
+                debug!("{:?}", timed_event);
+                received_events = true;
+                let instant = timed_event.instant;
+                let event = timed_event.event;
+
+                match event {
+                    Event::PlayerEvent(PlayerEvent::Seeked { position }) => {
+                        if let Some(ref mut ps) = player_state {
+                            ps.position_snapshot = PositionSnapshot { position, instant };
+                        }
+                    }
+                    Event::PlayerEvent(PlayerEvent::PlaybackStatusChange(
+                        PlaybackStatus::Playing,
+                    )) => {
+                        // position was already queried on pause and seek
+                        player_state = player_state.map(|p| PlayerState {
+                            playback_status: PlaybackStatus::Playing,
+                            position_snapshot: PositionSnapshot {
+                                position: p.position_snapshot.position,
+                                instant,
+                            },
+                            metadata: p.metadata,
+                        });
+                        ipc_server.on_playback_status_changed(PlaybackStatus::Playing);
+                    }
+                    Event::PlayerEvent(PlayerEvent::PlaybackStatusChange(
+                        PlaybackStatus::Stopped,
+                    )) => {
+                        player_state = Some(PlayerState {
+                            playback_status: PlaybackStatus::Stopped,
+                            position_snapshot: PositionSnapshot {
+                                position: Duration::from_millis(0),
+                                instant,
+                            },
+                            metadata: None,
+                        });
+                        ipc_server.on_playback_status_changed(PlaybackStatus::Stopped);
+                    }
+                    Event::PlayerEvent(PlayerEvent::PlaybackStatusChange(
+                        PlaybackStatus::Paused,
+                    )) => {
+                        if let (Some(p), Some(q)) = (&mut player_state, &player_query) {
+                            p.playback_status = PlaybackStatus::Paused;
+                            p.position_snapshot = PositionSnapshot {
+                                position: q.query_player_position().unwrap(),
+                                instant: Instant::now(),
+                            };
+                        }
+                        ipc_server.on_playback_status_changed(PlaybackStatus::Paused);
+                    }
+                    Event::PlayerEvent(PlayerEvent::TrackListChange(tracks)) => {
+                        let queued_paths: HashSet<PathBuf> =
+                            tracks.iter().filter_map(get_lrc_filepath).collect();
+                        upcoming_lyrics.retain(|path, _| queued_paths.contains(path));
+                        for path in queued_paths {
+                            prefetch_lrc(&mut upcoming_lyrics, path);
+                        }
+                    }
+                    Event::PlayerEvent(PlayerEvent::MetadataChange(metadata)) => {
+                        if lrc_filepath.is_none() {
+                            let path = metadata.as_ref().and_then(get_lrc_filepath);
+                            LrcManager::change_watched_path(path.clone(), &lrc_manager_sender);
+                            if let Some(cached) = path.and_then(|p| upcoming_lyrics.remove(&p)) {
+                                lyrics = Some(cached);
+                                for sink in &sinks {
+                                    sink.lyrics_changed(lyrics.as_ref().map(|l| l.lines.as_slice()));
+                                }
+                            }
+                        }
+                        ipc_server.on_metadata_changed(
+                            metadata.as_ref().and_then(|m| m.title.as_deref()),
+                            metadata.as_ref().and_then(|m| m.artist.as_deref()),
+                            metadata.as_ref().and_then(|m| m.album.as_deref()),
+                        );
+                        if let Some(ref mut p) = player_state {
+                            p.metadata = metadata;
+                        }
+                    }
+                    Event::PlayerEvent(PlayerEvent::PlayerShutDown) => {
+                        LrcManager::change_watched_path(None, &lrc_manager_sender);
+                        player_state = None;
+                        player_query = None;
+                    }
+                    Event::PlayerEvent(PlayerEvent::PlayerStarted {
+                        player_owner_name: n,
+                    }) => {
+                        // Auto mode re-fires this every time focus switches
+                        // to the most-recently-playing bus, including one
+                        // that's already vanished by the time we connect to
+                        // it; treat that as no active player rather than
+                        // taking the whole loop down with it.
+                        let connected = backend.connect(&n).and_then(|q| {
+                            let state = q.query_player_state()?;
+                            Ok((q, state))
+                        });
+                        match connected {
+                            Ok((q, state)) => {
+                                player_state = Some(state);
+                                player_query = Some(q);
+
+                                if lrc_filepath.is_none() {
+                                    let path = player_state
+                                        .as_ref()
+                                        .and_then(|p| p.metadata.as_ref())
+                                        .and_then(get_lrc_filepath);
+                                    LrcManager::change_watched_path(path.clone(), &lrc_manager_sender);
+                                    if let Some(cached) = path.and_then(|p| upcoming_lyrics.remove(&p)) {
+                                        lyrics = Some(cached);
+                                        for sink in &sinks {
+                                            sink.lyrics_changed(lyrics.as_ref().map(|l| l.lines.as_slice()));
+                                        }
+                                    }
+                                }
+                            }
+                            Err(e) => {
+                                warn!("Failed to connect to player {n:?} on start: {e}");
+                                player_state = None;
+                                player_query = None;
+                            }
+                        }
+                    }
+                    Event::PlayerEvent(PlayerEvent::Unknown {
+                        key: unknown_key,
+                        value: unknown_value,
+                    }) => {
+                        warn!("Unknown player event property: {unknown_key} = {unknown_value}");
+                    }
+                    Event::LyricsEvent(LyricsEvent::LyricsChanged { lyrics: l, .. }) => {
+                        lrc_state = None; // will be asigned after event processing
+                        lyrics = l;
+                        if let (Some(declared), Some(actual)) = (
+                            lyrics.as_ref().and_then(Lyrics::declared_length),
+                            player_state
+                                .as_ref()
+                                .and_then(|p| p.metadata.as_ref())
+                                .and_then(|m| m.length),
+                        ) {
+                            let diff = declared.as_secs_f64() - actual.as_secs_f64();
+                            if diff.abs() > 2.0 {
+                                warn!(
+                                    "Loaded .lrc declares length {:?} but player reports {:?}; \
+                                     the file may not match the playing track",
+                                    declared, actual
+                                );
+                            }
+                        }
+                        for sink in &sinks {
+                            sink.lyrics_changed(lyrics.as_ref().map(|l| l.lines.as_slice()));
+                        }
+                    }
+                }
+
+                debug!("player_state = {:?}", player_state);
+            }
+        }
+
+        // Print new lyrics line, if needed
+        if received_events {
+            lrc_state = lyrics.as_ref().and_then(|l| {
+                player_state
+                    .as_ref()
+                    .map(|p| LrcTimedTextState::new(l, p.current_position()))
+            });
+            let timed_text = lrc_state.as_ref().and_then(|l| l.current);
+            for sink in &sinks {
+                sink.active_segment_changed(timed_text.and_then(|t| line_text(&lyrics, t)), timed_text);
+            }
+            let next_text = lrc_state.as_ref().and_then(|l| l.next);
+            ipc_server.on_current_line_changed(
+                timed_text.and_then(|t| line_text(&lyrics, t)),
+                timed_text.map(|t| t.line_index),
+                timed_text.map(|t| t.line_char_from_index),
+                timed_text.map(|t| t.line_char_to_index),
+            );
+            ipc_server.on_next_line_changed(
+                next_text.and_then(|t| line_text(&lyrics, t)),
+                next_text.map(|t| t.line_index),
+            );
+            if let Some(ref i3bar_output) = i3bar_output {
+                i3bar_output.on_current_line_changed(timed_text.and_then(|t| line_text(&lyrics, t)));
+            }
+        } else if let Some(ref player_state) = player_state {
+            if player_state.playback_status == PlaybackStatus::Playing {
+                let new_timed_text = lrc_state
+                    .as_mut()
+                    .and_then(|l| l.on_position_advanced(player_state.current_position()));
+                // None also means that current lyrics segment should not change
+                if new_timed_text.is_some() {
+                    for sink in &sinks {
+                        sink.active_segment_changed(
+                            new_timed_text.and_then(|t| line_text(&lyrics, t)),
+                            new_timed_text,
+                        );
+                    }
+                    let next_text = lrc_state.as_ref().and_then(|l| l.next);
+                    ipc_server.on_current_line_changed(
+                        new_timed_text.and_then(|t| line_text(&lyrics, t)),
+                        new_timed_text.map(|t| t.line_index),
+                        new_timed_text.map(|t| t.line_char_from_index),
+                        new_timed_text.map(|t| t.line_char_to_index),
+                    );
+                    ipc_server.on_next_line_changed(
+                        next_text.and_then(|t| line_text(&lyrics, t)),
+                        next_text.map(|t| t.line_index),
+                    );
+                    if let Some(ref i3bar_output) = i3bar_output {
+                        i3bar_output.on_current_line_changed(
+                            new_timed_text.and_then(|t| line_text(&lyrics, t)),
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
@@ -9,6 +9,8 @@ use std::path::Path;
 #[allow(unused_imports)]
 use log::{debug, error, info, trace, warn};
 
+use crate::formatters::format_duration_floor;
+
 fn lines_from_file<P: AsRef<Path>>(filepath: P) -> Result<Vec<String>, String> {
     let file = File::open(filepath).map_err(|e| e.to_string())?;
     io::BufReader::new(file)
@@ -41,10 +43,17 @@ pub struct TimedText {
     pub timings: Vec<TimedLocation>,
 }
 
+/// Standard LRC ID tags (`[ti:...]`, `[ar:...]`, ...), carried through as
+/// raw key/value pairs rather than interpreted, except for `length` which
+/// is also parsed into a `Duration` for comparing against the player's
+/// reported track length.
+const LRC_ID_TAG_KEYS: &[&str] = &["ti", "ar", "al", "au", "by", "length", "re", "ve"];
+
 #[derive(Debug)]
 enum Tag {
     Time(std::time::Duration),
     Offset(i64), // ms
+    Id { key: String, value: String },
     Unknown,
 }
 
@@ -101,7 +110,10 @@ fn parse_tag(tag_content: &str) -> Result<Tag, String> {
         let time = duration_from_time_string(tag_content)?;
         Ok(Tag::Time(time))
     } else {
-        let mut parts = tag_content.split(':');
+        // splitn(2, ..) rather than split(..): an ID tag's value (e.g.
+        // `length:03:45`) may itself contain colons, and only the first one
+        // separates the tag name from its value.
+        let mut parts = tag_content.splitn(2, ':');
         let tag_first_part = parts
             .next()
             .expect("Should never happen; split always returns at least one element");
@@ -115,11 +127,79 @@ fn parse_tag(tag_content: &str) -> Result<Tag, String> {
                 })?;
                 Ok(Tag::Offset(offset))
             }
+            key if LRC_ID_TAG_KEYS.contains(&key) => Ok(Tag::Id {
+                key: key.to_owned(),
+                value: parts.next().unwrap_or("").trim().to_owned(),
+            }),
             _ => Ok(Tag::Unknown),
         }
     }
 }
 
+/// Parses a `[length:mm:ss]` tag's value into a `Duration`, for comparing
+/// against the player's reported track length.
+fn parse_length_tag(value: &str) -> Option<Duration> {
+    let (minutes_str, seconds_str) = value.split_once(':')?;
+    let minutes: u64 = minutes_str.trim().parse().ok()?;
+    let seconds: u64 = seconds_str.trim().parse().ok()?;
+    Some(Duration::from_secs((minutes * 60) + seconds))
+}
+
+/// Parses the enhanced ("A2") extension text following a `[time]` tag, where
+/// inline `<mm:ss.xx>` tags mark the start of each following word, e.g.
+/// `word1 <00:12.50>word2 <00:13.10>word3`. Returns the tag-free text, its
+/// length and one `TimedLocation` per word: the first word starts at
+/// `line_time` (the enclosing `[time]` tag), each following word starts at
+/// its own `<time>` tag. Text with no inline tags is returned unchanged,
+/// with a single `TimedLocation` spanning the whole text.
+fn parse_word_timings(
+    raw_text: &str,
+    line_time: Duration,
+    start_char_index: i32,
+) -> Result<(String, i32, Vec<TimedLocation>), String> {
+    if !raw_text.contains('<') {
+        let text_len: i32 = raw_text.bytes().len().try_into().unwrap();
+        return Ok((
+            raw_text.to_owned(),
+            text_len,
+            vec![TimedLocation {
+                time: line_time,
+                line_char_from_index: start_char_index,
+                line_char_to_index: start_char_index + text_len,
+            }],
+        ));
+    }
+
+    let mut parts = raw_text.split('<');
+    let first = parts.next().unwrap_or("");
+    let mut clean_text = String::from(first);
+    let mut current_index = start_char_index + i32::try_from(first.bytes().len()).unwrap();
+    let mut timings = vec![TimedLocation {
+        time: line_time,
+        line_char_from_index: start_char_index,
+        line_char_to_index: current_index,
+    }];
+
+    for part in parts {
+        let mut word_parts = part.splitn(2, '>');
+        let time_str = word_parts
+            .next()
+            .ok_or_else(|| format!("Missing '>' in inline word timestamp tag: {}", part))?;
+        let word = word_parts.next().unwrap_or("");
+        let word_time = duration_from_time_string(time_str)?;
+        clean_text.push_str(word);
+        let word_len: i32 = word.bytes().len().try_into().unwrap();
+        timings.push(TimedLocation {
+            time: word_time,
+            line_char_from_index: current_index,
+            line_char_to_index: current_index + word_len,
+        });
+        current_index += word_len;
+    }
+
+    Ok((clean_text, current_index - start_char_index, timings))
+}
+
 fn parse_lrc_line(line: String) -> Result<LrcLine, String> {
     trace!("Parsing line {}", line);
     match line.chars().next() {
@@ -129,33 +209,51 @@ fn parse_lrc_line(line: String) -> Result<LrcLine, String> {
             let parts = line.split('[');
             let mut timings = Vec::new();
             let mut texts = Vec::new();
+            // Leading `[time]` tags with no text of their own (consecutive,
+            // before anything is written) mark a repeated line sung at
+            // several times, e.g. `[00:12.00][01:15.00]chorus text`; their
+            // timings span the whole line rather than a particular word, so
+            // they're collected separately and only resolved to a char
+            // range once the full line text is known.
+            let mut leading_times = Vec::new();
+            let mut seen_text = false;
             for part in parts.skip(1) {
                 let mut subparts = part.split(']');
                 let tag_content = subparts
                     .next()
                     .expect("Should never happen; split always returns at least one element");
-                let mut text_len: i32 = 0;
-
-                if let Some(text) = subparts.next() {
-                    texts.push(text);
-                    text_len = text.bytes().len().try_into().unwrap();
-                }
+                let raw_text = subparts.next().unwrap_or("");
 
                 match parse_tag(tag_content)? {
+                    Tag::Time(time) if raw_text.is_empty() && !seen_text => {
+                        leading_times.push(time);
+                    }
                     Tag::Time(time) => {
-                        let location = TimedLocation {
-                            time,
-                            line_char_from_index: current_text_index_in_line,
-                            line_char_to_index: current_text_index_in_line + text_len,
-                        };
-                        timings.push(location);
+                        seen_text = true;
+                        let (clean_text, text_len, word_timings) =
+                            parse_word_timings(raw_text, time, current_text_index_in_line)?;
+                        texts.push(clean_text);
+                        timings.extend(word_timings);
                         current_text_index_in_line += text_len;
                     }
                     tag => return Ok(LrcLine::Tag(tag)),
                 }
             }
             let text = texts.join("");
-            Ok(LrcLine::TimedText(TimedText { text, timings }))
+            let full_line_len: i32 = text.bytes().len().try_into().unwrap();
+            let mut all_timings: Vec<TimedLocation> = leading_times
+                .into_iter()
+                .map(|time| TimedLocation {
+                    time,
+                    line_char_from_index: 0,
+                    line_char_to_index: full_line_len,
+                })
+                .collect();
+            all_timings.extend(timings);
+            Ok(LrcLine::TimedText(TimedText {
+                text,
+                timings: all_timings,
+            }))
         }
         Some(c) => {
             let mut buf = [0; 10];
@@ -171,6 +269,7 @@ fn parse_lrc_line(line: String) -> Result<LrcLine, String> {
 pub fn parse_lrc_file<P: AsRef<Path>>(filepath: P) -> Result<LrcFile, String> {
     let text_lines = lines_from_file(filepath)?;
     let mut timed_texts_lines = Vec::new();
+    let mut metadata = Vec::new();
     let mut offset_ms = 0i64;
     for line in text_lines {
         match parse_lrc_line(line)? {
@@ -185,11 +284,12 @@ pub fn parse_lrc_file<P: AsRef<Path>>(filepath: P) -> Result<LrcFile, String> {
                 timed_texts_lines.push(t);
             }
             LrcLine::Tag(Tag::Offset(v)) => offset_ms = v,
+            LrcLine::Tag(Tag::Id { key, value }) => metadata.push((key, value)),
             _ => {}
         }
     }
     Ok(LrcFile {
-        metadata: Vec::new(),
+        metadata,
         timed_texts_lines,
     })
 }
@@ -202,10 +302,13 @@ pub struct LyricsTiming {
     pub line_char_to_index: i32,   // to this character in line
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Lyrics {
     pub lines: Vec<String>,
     pub timings: Vec<LyricsTiming>,
+    /// The `.lrc` file's ID tags (`ti`, `ar`, `al`, ...), in file order, for
+    /// display and for `declared_length`'s track-duration comparison.
+    pub metadata: Vec<(String, String)>,
 }
 
 impl Lyrics {
@@ -233,6 +336,173 @@ impl Lyrics {
                 })
             }
         }
-        Lyrics { lines, timings }
+        // A repeated line (see `parse_lrc_line`) contributes several
+        // timings at once, not necessarily adjacent to the rest of the
+        // timeline; re-sort so `LrcTimedTextState` can keep relying on
+        // `timings` being in ascending time order.
+        timings.sort_by_key(|t| t.time);
+        Lyrics {
+            lines,
+            timings,
+            metadata: lrc_file.metadata,
+        }
+    }
+
+    /// The file's own `[length:mm:ss]` tag, if present, for comparing
+    /// against the player-reported track duration.
+    pub fn declared_length(&self) -> Option<Duration> {
+        self.metadata
+            .iter()
+            .find(|(key, _)| key == "length")
+            .and_then(|(_, value)| parse_length_tag(value))
+    }
+}
+
+/// Writes `lyrics` back out as an enhanced ("A2") LRC file. Timings are
+/// walked in time order; each run of consecutive timings on the same lyric
+/// line becomes one output line, with a `[mm:ss.xx]` tag for the first word
+/// and a `<mm:ss.xx>` tag before every following word. A line whose timings
+/// are split by another line's (a repeated line) is written once per run.
+/// Lines with a single timing (no word-level detail) are written as plain
+/// `[mm:ss.xx]text` lines.
+pub fn format_as_enhanced_lrc(lyrics: &Lyrics) -> String {
+    use std::fmt::Write;
+
+    let mut output = String::new();
+    let mut timings = lyrics.timings.iter().peekable();
+
+    // Skip the synthetic zero-time bootstrap entry Lyrics::new inserts
+    // ahead of the first real line; it has no text of its own. Keyed on
+    // `Lyrics::new`'s exact values rather than an empty char range, since a
+    // legitimate leading instrumental timing (e.g. `[00:12]` with no text)
+    // also has `from == to` and must not be dropped.
+    if let Some(first) = timings.peek() {
+        if first.time == Duration::ZERO && first.line_index == 0 {
+            timings.next();
+        }
+    }
+
+    while let Some(timing) = timings.next() {
+        let Some(line_text) = lyrics.lines.get(timing.line_index as usize) else {
+            continue;
+        };
+        let mut line_out = String::new();
+        write!(line_out, "[{}]", format_duration_floor(&timing.time)).unwrap();
+        let from = timing.line_char_from_index as usize;
+        let to = timing.line_char_to_index as usize;
+        line_out.push_str(&line_text[from..to]);
+
+        while let Some(next_timing) = timings.peek() {
+            if next_timing.line_index != timing.line_index {
+                break;
+            }
+            let next_timing = timings.next().unwrap();
+            write!(line_out, "<{}>", format_duration_floor(&next_timing.time)).unwrap();
+            let from = next_timing.line_char_from_index as usize;
+            let to = next_timing.line_char_to_index as usize;
+            line_out.push_str(&line_text[from..to]);
+        }
+
+        output.push_str(&line_out);
+        output.push('\n');
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_timed_text(line: &str) -> TimedText {
+        match parse_lrc_line(line.to_owned()).unwrap() {
+            LrcLine::TimedText(t) => t,
+            other => panic!("expected TimedText, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_lrc_line_repeated_timestamps() {
+        let t = parse_timed_text("[00:12.00][01:15.00]chorus text");
+        assert_eq!(t.text, "chorus text");
+        assert_eq!(t.timings.len(), 2);
+        let full_len: i32 = t.text.bytes().len().try_into().unwrap();
+        for timing in &t.timings {
+            assert_eq!(timing.line_char_from_index, 0);
+            assert_eq!(timing.line_char_to_index, full_len);
+        }
+        assert_eq!(t.timings[0].time, Duration::from_secs(12));
+        assert_eq!(t.timings[1].time, Duration::from_secs(75));
+    }
+
+    #[test]
+    fn test_parse_lrc_line_word_timings() {
+        let t = parse_timed_text("[00:12.00]word1 <00:12.50>word2");
+        assert_eq!(t.text, "word1 word2");
+        assert_eq!(t.timings.len(), 2);
+        assert_eq!(t.timings[0].time, Duration::from_secs(12));
+        assert_eq!(t.timings[0].line_char_from_index, 0);
+        assert_eq!(t.timings[0].line_char_to_index, 6);
+        assert_eq!(t.timings[1].time, Duration::from_millis(12500));
+        assert_eq!(t.timings[1].line_char_from_index, 6);
+        assert_eq!(t.timings[1].line_char_to_index, 11);
+    }
+
+    #[test]
+    fn test_lyrics_keeps_global_time_order_with_repeated_lines() {
+        let chorus = parse_timed_text("[00:12.00][01:15.00]chorus");
+        let verse = parse_timed_text("[00:30.00]verse");
+        let lrc_file = LrcFile {
+            metadata: Vec::new(),
+            timed_texts_lines: vec![chorus, verse],
+        };
+        let lyrics = Lyrics::new(lrc_file);
+
+        let times: Vec<Duration> = lyrics.timings.iter().map(|t| t.time).collect();
+        let mut sorted_times = times.clone();
+        sorted_times.sort();
+        assert_eq!(times, sorted_times, "timings must stay in ascending time order");
+
+        let verse_pos = times
+            .iter()
+            .position(|&t| t == Duration::from_secs(30))
+            .unwrap();
+        let chorus_repeat_pos = times
+            .iter()
+            .position(|&t| t == Duration::from_secs(75))
+            .unwrap();
+        assert!(chorus_repeat_pos > verse_pos);
+    }
+
+    #[test]
+    fn test_format_as_enhanced_lrc_round_trip() {
+        let line1 = parse_timed_text("[00:12.00]word1 <00:12.50>word2");
+        let line2 = parse_timed_text("[00:30.00]verse");
+        let lrc_file = LrcFile {
+            metadata: Vec::new(),
+            timed_texts_lines: vec![line1, line2],
+        };
+        let lyrics = Lyrics::new(lrc_file);
+
+        assert_eq!(
+            format_as_enhanced_lrc(&lyrics),
+            "[00:12.00]word1 <00:12.50>word2\n[00:30.00]verse\n"
+        );
+    }
+
+    #[test]
+    fn test_format_as_enhanced_lrc_keeps_leading_instrumental_timing() {
+        // A line with no text of its own (e.g. `[00:12]` with nothing after
+        // it) has line_char_from_index == line_char_to_index, same as the
+        // synthetic bootstrap entry `Lyrics::new` inserts ahead of the first
+        // line; it must still be written out, not mistaken for that entry.
+        let instrumental = parse_timed_text("[00:12.00]");
+        let lrc_file = LrcFile {
+            metadata: Vec::new(),
+            timed_texts_lines: vec![instrumental],
+        };
+        let lyrics = Lyrics::new(lrc_file);
+
+        assert_eq!(format_as_enhanced_lrc(&lyrics), "[00:12.00]\n");
     }
 }
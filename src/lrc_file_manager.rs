@@ -1,26 +1,178 @@
-use std::path::PathBuf;
-use std::sync::mpsc::channel;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, RecvTimeoutError};
 use std::thread;
 use std::time::{Duration, Instant};
 
-use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use notify::event::ModifyKind;
+use notify::{EventKind, PollWatcher, RecommendedWatcher, RecursiveMode, Watcher};
 
 #[allow(unused_imports)]
 use log::{debug, error, info, trace, warn};
 
-use crate::events::{Event, LyricsEvent, Metadata, TimedEvent};
+use crate::events::{Event, LyricsEvent, Metadata, MetadataLocation, TimedEvent};
 use crate::lrc::{parse_lrc_file, Lyrics};
 
 pub enum InputEvents {
     ChangePath(Option<PathBuf>),
-    FileChanged(PathBuf),
+    FileChanged(PathBuf, EventKind),
+}
+
+/// Which filesystem-watching backend to follow the `.lrc` file's parent
+/// directory with. Native (inotify/FSEvents/...) is cheap and reacts
+/// instantly, but doesn't fire reliably on network shares, SSHFS, or
+/// cloud-synced folders; `Poll` trades that for a fixed-interval scan that
+/// works everywhere.
+#[derive(Clone, Copy, Debug)]
+pub enum WatcherKind {
+    Native,
+    Poll(Duration),
+}
+
+/// Editors that save via write-to-temp-then-rename emit a burst of
+/// `Create`/`Modify`/`Remove` events for the same path; waiting for this long
+/// of quiet after the last one before reloading coalesces the burst into a
+/// single reload and avoids momentarily reporting the file as missing mid-save.
+const DEBOUNCE_WINDOW: Duration = Duration::from_millis(200);
+
+/// Builds a watcher of the given kind, forwarding its events to `handler`.
+/// Generic over `notify::EventHandler` so both the sync path (a plain
+/// `std::sync::mpsc::Sender`, which `notify` implements `EventHandler` for)
+/// and the async path (a closure forwarding into a `tokio::sync::mpsc`
+/// channel) can share this construction logic.
+fn make_watcher<H: notify::EventHandler>(kind: WatcherKind, handler: H) -> Box<dyn Watcher + Send> {
+    match kind {
+        WatcherKind::Native => {
+            Box::new(RecommendedWatcher::new(handler, notify::Config::default()).unwrap())
+        }
+        WatcherKind::Poll(interval) => {
+            // Without this, `PollWatcher` reports every mtime change as
+            // `Modify(Metadata(..))`/`Modify(Any)` instead of
+            // `Modify(Data(..))`, which `translate_watcher_event` filters
+            // out — silently breaking reloads in poll mode.
+            let notify_config = notify::Config::default()
+                .with_poll_interval(interval)
+                .with_compare_contents(true);
+            Box::new(PollWatcher::new(handler, notify_config).unwrap())
+        }
+    }
+}
+
+/// Picks out the path and kind of interest from a raw watcher event, if any,
+/// keeping only the event kinds `on_fs_event` acts on (`Modify`s that are
+/// only a metadata touch, not a content change, are filtered out here).
+/// `Modify(Name(_))` covers a rename-in on native (inotify) backends: the
+/// atomic save-by-rename this module specifically targets arrives as a
+/// rename, not a `Create`, there. Shared by the sync and async
+/// watcher-forwarding loops.
+fn translate_watcher_event(mut event: notify::Event) -> Option<(PathBuf, EventKind)> {
+    match event.kind {
+        EventKind::Create(_)
+        | EventKind::Modify(ModifyKind::Data(_))
+        | EventKind::Modify(ModifyKind::Name(_))
+        | EventKind::Remove(_) => {
+            let path = event.paths.pop()?;
+            Some((path, event.kind))
+        }
+        _ => None,
+    }
+}
+
+/// Adds a direct, non-recursive watch on `file_path` if it currently exists.
+/// Used both for the initial watch and to re-establish it after a
+/// rename-replace (the direct watch is dropped by the OS once the `Remove`
+/// half of the swap happens).
+fn watch_file_direct(watcher: &mut (dyn Watcher + Send), file_path: &Path) {
+    if file_path.is_file() {
+        if let Err(e) = watcher.watch(file_path, RecursiveMode::NonRecursive) {
+            error!("Failed to watch {:?} for changes: {}", file_path, e);
+        }
+    }
+}
+
+/// Watches `file_path` directly (`NonRecursive`) so its own `Modify`/`Remove`
+/// events are reported without flooding the channel with unrelated siblings,
+/// plus its parent directory (also `NonRecursive`) so a late creation or an
+/// atomic save-by-rename of a currently-missing file is still picked up.
+fn start_watch(watcher: &mut (dyn Watcher + Send), file_path: &Path) {
+    if let Some(parent) = file_path.parent() {
+        if let Err(e) = watcher.watch(parent, RecursiveMode::NonRecursive) {
+            error!("Failed to watch {:?} for changes: {}", parent, e);
+        }
+    }
+    watch_file_direct(watcher, file_path);
+    debug!("Watching {:?} for changes", file_path);
+}
+
+/// Undoes `start_watch`. Unwatching the file itself is allowed to fail
+/// silently: it may already be gone, or never have existed, and `notify`
+/// errors out of `unwatch` for paths it isn't watching.
+fn stop_watch(watcher: &mut (dyn Watcher + Send), file_path: &Path) {
+    if let Some(parent) = file_path.parent() {
+        if let Err(e) = watcher.unwatch(parent) {
+            error!("Failed to unwatch {:?}: {}", parent, e);
+        }
+    }
+    let _ = watcher.unwatch(file_path);
+    debug!("Stopped watching {:?} for changes", file_path);
+}
+
+/// Moves the watch from `old_path` to `new_path`, shared by the sync and
+/// async `ChangePath` handling.
+fn rewatch(watcher: &mut (dyn Watcher + Send), old_path: &Option<PathBuf>, new_path: &Option<PathBuf>) {
+    if let Some(old_file_path) = old_path {
+        stop_watch(watcher, old_file_path);
+    }
+    if let Some(new_file_path) = new_path {
+        start_watch(watcher, new_file_path);
+    }
+}
+
+/// Reloads and reports the `.lrc` file at `lrc_filepath` if `changed_file_path`
+/// is the file currently being watched. Shared by the sync and async paths.
+fn reload_lyrics(
+    lrc_filepath: &Option<PathBuf>,
+    changed_file_path: Option<PathBuf>,
+    lyric_event_tx: &std::sync::mpsc::Sender<TimedEvent>,
+) {
+    if changed_file_path != *lrc_filepath {
+        return;
+    }
+    let lyrics = {
+        if let Some(file_path) = lrc_filepath {
+            if file_path.is_file() {
+                let lrc_file = parse_lrc_file(file_path)
+                    .map_err(|e| error!("Parsing lrc file {:?} failed: {}", file_path, e))
+                    .ok();
+                if lrc_file.is_some() {
+                    info!("Lyrics file loaded: {:?}", file_path);
+                }
+                lrc_file.map(Lyrics::new)
+            } else {
+                info!("Lyrics file not found at {:?}", file_path);
+                None
+            }
+        } else {
+            None
+        }
+    };
+
+    lyric_event_tx
+        .send(TimedEvent {
+            instant: Instant::now(),
+            event: Event::LyricsEvent(LyricsEvent::LyricsChanged {
+                lyrics,
+                file_path: changed_file_path,
+            }),
+        })
+        .unwrap();
 }
 
 pub struct LrcManager {
     tx: std::sync::mpsc::Sender<InputEvents>,
     rx: std::sync::mpsc::Receiver<InputEvents>,
     lyric_event_tx: std::sync::mpsc::Sender<TimedEvent>,
-    watcher: RecommendedWatcher,
+    watcher: Box<dyn Watcher + Send>,
     lrc_filepath: Option<PathBuf>,
 }
 
@@ -37,31 +189,44 @@ impl LrcManager {
         self.tx.clone()
     }
 
-    pub fn new(lyric_event_tx: std::sync::mpsc::Sender<TimedEvent>) -> Self {
+    pub fn new(lyric_event_tx: std::sync::mpsc::Sender<TimedEvent>, watcher_kind: WatcherKind) -> Self {
         let (watcher_tx, watcher_rx) = channel();
-        let notify_config =
-            notify::Config::default().with_poll_interval(Duration::from_millis(100));
-        let watcher = RecommendedWatcher::new(watcher_tx, notify_config).unwrap();
+        let watcher = make_watcher(watcher_kind, watcher_tx);
 
         let (tx, rx) = channel();
         {
             let tx_clone = tx.clone();
-            thread::spawn(move || loop {
-                match watcher_rx.recv() {
-                    Ok(Ok(mut event)) => {
-                        debug!("Watcher event: {:?}", event);
-                        match event.kind {
-                            notify::EventKind::Create(_)
-                            | notify::EventKind::Modify(_)
-                            | notify::EventKind::Remove(_) => {
-                                let path = event.paths.pop().unwrap();
-                                tx_clone.send(InputEvents::FileChanged(path)).unwrap();
+            thread::spawn(move || {
+                let mut pending: HashMap<PathBuf, (Instant, EventKind)> = HashMap::new();
+                loop {
+                    let timeout = pending
+                        .values()
+                        .map(|(seen_at, _)| DEBOUNCE_WINDOW.saturating_sub(seen_at.elapsed()))
+                        .min()
+                        .unwrap_or(DEBOUNCE_WINDOW);
+                    match watcher_rx.recv_timeout(timeout) {
+                        Ok(Ok(event)) => {
+                            debug!("Watcher event: {:?}", event);
+                            if let Some((path, kind)) = translate_watcher_event(event) {
+                                pending.insert(path, (Instant::now(), kind));
                             }
-                            _ => {}
                         }
+                        Ok(Err(_)) | Err(RecvTimeoutError::Disconnected) => {
+                            return;
+                        }
+                        Err(RecvTimeoutError::Timeout) => {}
                     }
-                    Ok(Err(_)) | Err(_) => {
-                        return;
+
+                    let quiet_paths: Vec<PathBuf> = pending
+                        .iter()
+                        .filter(|(_, (seen_at, _))| seen_at.elapsed() >= DEBOUNCE_WINDOW)
+                        .map(|(path, _)| path.clone())
+                        .collect();
+                    for path in quiet_paths {
+                        let (_, kind) = pending.remove(&path).unwrap();
+                        if tx_clone.send(InputEvents::FileChanged(path, kind)).is_err() {
+                            return;
+                        }
                     }
                 }
             });
@@ -75,58 +240,31 @@ impl LrcManager {
         }
     }
 
-    fn on_file_changed(&self, changed_file_path: Option<PathBuf>) {
-        if changed_file_path == self.lrc_filepath {
-            let lyrics = {
-                if let Some(file_path) = &self.lrc_filepath {
-                    if file_path.is_file() {
-                        let lrc_file = parse_lrc_file(file_path)
-                            .map_err(|e| error!("Parsing lrc file {:?} failed: {}", file_path, e))
-                            .ok();
-                        if lrc_file.is_some() {
-                            info!("Lyrics file loaded: {:?}", file_path);
-                        }
-                        lrc_file.map(Lyrics::new)
-                    } else {
-                        info!("Lyrics file not found at {:?}", file_path);
-                        None
-                    }
-                } else {
-                    None
-                }
-            };
-
-            self.lyric_event_tx
-                .send(TimedEvent {
-                    instant: Instant::now(),
-                    event: Event::LyricsEvent(LyricsEvent::LyricsChanged {
-                        lyrics,
-                        file_path: changed_file_path,
-                    }),
-                })
-                .unwrap();
+    fn on_fs_event(&mut self, changed_file_path: PathBuf, kind: EventKind) {
+        if Some(&changed_file_path) != self.lrc_filepath.as_ref() {
+            return;
+        }
+        if matches!(kind, EventKind::Create(_) | EventKind::Modify(ModifyKind::Name(_))) {
+            // Always re-assert the direct watch on `Create` (or, on native
+            // backends, the `Modify(Name(_))` rename-in that a save-by-rename
+            // produces there instead): debouncing coalesces an atomic save's
+            // `Remove` immediately followed by one of these into just the
+            // single event we see here, so there's no reliable "did we just
+            // lose the watch" signal left to gate on. Re-adding an
+            // already-active watch is a harmless no-op.
+            watch_file_direct(self.watcher.as_mut(), &changed_file_path);
         }
+        reload_lyrics(&self.lrc_filepath, Some(changed_file_path), &self.lyric_event_tx);
     }
 
     pub fn run_sync(&mut self) -> Result<(), ()> {
         loop {
             match self.rx.recv().map_err(|_| ())? {
-                InputEvents::FileChanged(file_path) => self.on_file_changed(Some(file_path)),
+                InputEvents::FileChanged(file_path, kind) => self.on_fs_event(file_path, kind),
                 InputEvents::ChangePath(file_path) => {
-                    if let Some(old_file_path) = &self.lrc_filepath {
-                        let old_folder_path = old_file_path.parent().unwrap();
-                        self.watcher.unwatch(old_folder_path).unwrap();
-                        debug!("Stopped watching {:?} for changes", old_folder_path);
-                    }
+                    rewatch(self.watcher.as_mut(), &self.lrc_filepath, &file_path);
                     self.lrc_filepath.clone_from(&file_path);
-                    if let Some(new_file_path) = &self.lrc_filepath {
-                        let new_folder_path = new_file_path.parent().unwrap();
-                        self.watcher
-                            .watch(new_folder_path, RecursiveMode::Recursive)
-                            .unwrap();
-                        debug!("Watching {:?} for changes", new_folder_path);
-                    }
-                    self.on_file_changed(file_path);
+                    reload_lyrics(&self.lrc_filepath, file_path, &self.lyric_event_tx);
                 }
             }
         }
@@ -139,8 +277,129 @@ impl LrcManager {
     }
 }
 
-pub fn get_lrc_filepath(metadata: &Metadata) -> PathBuf {
-    let mut lrc_filepath = metadata.file_path.clone();
-    lrc_filepath.set_extension("lrc");
-    lrc_filepath
+/// Async counterpart to `LrcManager`, for embedding in a tokio runtime
+/// instead of bridging across a dedicated OS thread: the watcher forwards
+/// events via an `EventHandler` closure straight into a `tokio::sync::mpsc`
+/// channel, and `run` is an `async fn` awaiting on that channel rather than
+/// blocking on `std::sync::mpsc::Receiver::recv`. Event translation
+/// (`translate_watcher_event`), watch/unwatch bookkeeping (`rewatch`), and
+/// LRC reloading (`reload_lyrics`) are shared with the sync path above.
+#[cfg(feature = "tokio-runtime")]
+pub struct AsyncLrcManager {
+    tx: tokio::sync::mpsc::UnboundedSender<InputEvents>,
+    rx: tokio::sync::mpsc::UnboundedReceiver<InputEvents>,
+    watcher_rx: tokio::sync::mpsc::UnboundedReceiver<notify::Result<notify::Event>>,
+    lyric_event_tx: std::sync::mpsc::Sender<TimedEvent>,
+    watcher: Box<dyn Watcher + Send>,
+    lrc_filepath: Option<PathBuf>,
+}
+
+#[cfg(feature = "tokio-runtime")]
+impl AsyncLrcManager {
+    pub fn change_watched_path(
+        file_path: Option<PathBuf>,
+        sender: &tokio::sync::mpsc::UnboundedSender<InputEvents>,
+    ) {
+        debug!("change_watched_path : {:?}", file_path);
+        sender.send(InputEvents::ChangePath(file_path)).unwrap();
+    }
+
+    pub fn clone_sender(&self) -> tokio::sync::mpsc::UnboundedSender<InputEvents> {
+        self.tx.clone()
+    }
+
+    pub fn new(lyric_event_tx: std::sync::mpsc::Sender<TimedEvent>, watcher_kind: WatcherKind) -> Self {
+        let (watcher_tx, watcher_rx) = tokio::sync::mpsc::unbounded_channel();
+        let watcher = make_watcher(watcher_kind, move |event| {
+            let _ = watcher_tx.send(event);
+        });
+
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        Self {
+            tx,
+            rx,
+            watcher_rx,
+            lyric_event_tx,
+            watcher,
+            lrc_filepath: None,
+        }
+    }
+
+    fn on_fs_event(&mut self, changed_file_path: PathBuf, kind: EventKind) {
+        if Some(&changed_file_path) != self.lrc_filepath.as_ref() {
+            return;
+        }
+        if matches!(kind, EventKind::Create(_) | EventKind::Modify(ModifyKind::Name(_))) {
+            // See the sync `LrcManager::on_fs_event` for why this is
+            // unconditional.
+            watch_file_direct(self.watcher.as_mut(), &changed_file_path);
+        }
+        reload_lyrics(&self.lrc_filepath, Some(changed_file_path), &self.lyric_event_tx);
+    }
+
+    pub async fn run(&mut self) -> Result<(), ()> {
+        let mut pending: HashMap<PathBuf, (Instant, EventKind)> = HashMap::new();
+        loop {
+            let timeout = pending
+                .values()
+                .map(|(seen_at, _)| DEBOUNCE_WINDOW.saturating_sub(seen_at.elapsed()))
+                .min()
+                .unwrap_or(DEBOUNCE_WINDOW);
+
+            tokio::select! {
+                event = self.watcher_rx.recv() => {
+                    match event.ok_or(())? {
+                        Ok(event) => {
+                            debug!("Watcher event: {:?}", event);
+                            if let Some((path, kind)) = translate_watcher_event(event) {
+                                pending.insert(path, (Instant::now(), kind));
+                            }
+                        }
+                        Err(_) => return Err(()),
+                    }
+                }
+                input = self.rx.recv() => {
+                    match input.ok_or(())? {
+                        InputEvents::FileChanged(file_path, kind) => {
+                            self.on_fs_event(file_path, kind);
+                        }
+                        InputEvents::ChangePath(file_path) => {
+                            rewatch(self.watcher.as_mut(), &self.lrc_filepath, &file_path);
+                            self.lrc_filepath.clone_from(&file_path);
+                            reload_lyrics(&self.lrc_filepath, file_path, &self.lyric_event_tx);
+                        }
+                    }
+                }
+                () = tokio::time::sleep(timeout) => {}
+            }
+
+            let quiet_paths: Vec<PathBuf> = pending
+                .iter()
+                .filter(|(_, (seen_at, _))| seen_at.elapsed() >= DEBOUNCE_WINDOW)
+                .map(|(path, _)| path.clone())
+                .collect();
+            for path in quiet_paths {
+                let (_, kind) = pending.remove(&path).unwrap();
+                self.on_fs_event(path, kind);
+            }
+        }
+    }
+}
+
+/// There's no local path to derive a sibling `.lrc` path from for a
+/// `Remote` location (streaming/podcast players, `spotify:` URIs); those
+/// need a tag-based or network lyric lookup instead, which isn't
+/// implemented yet.
+pub fn get_lrc_filepath(metadata: &Metadata) -> Option<PathBuf> {
+    match &metadata.location {
+        MetadataLocation::LocalFile(file_path) => {
+            let mut lrc_filepath = file_path.clone();
+            lrc_filepath.set_extension("lrc");
+            Some(lrc_filepath)
+        }
+        MetadataLocation::Remote(url) => {
+            debug!("No local .lrc lookup for remote track location: {url}");
+            None
+        }
+    }
 }
@@ -1,29 +1,53 @@
-mod events;
-mod formatters;
-mod lrc;
-mod lrc_file_manager;
-mod player;
-mod server;
-
 use std::path::PathBuf;
-use std::sync::mpsc::channel;
-use std::time::{Duration, Instant};
 
 use clap::Parser;
-use dbus::blocking::LocalConnection;
 
 #[allow(unused_imports)]
 use log::{debug, error, info, trace, warn};
 
-use crate::events::{
-    Event, LyricsEvent, PlaybackStatus, PlayerEvent, PlayerState, PositionSnapshot, TimedEvent,
-};
-use crate::formatters::format_duration;
-use crate::lrc::{Lyrics, LyricsTiming};
-use crate::lrc_file_manager::{get_lrc_filepath, LrcManager};
-use crate::player::{get_connection_proxy, PlayerNotifications, QueryPlayerProperties};
+use lrcshow_rs::backend::Player;
+use lrcshow_rs::i3bar::I3barConfig;
+use lrcshow_rs::lrc_file_manager::WatcherKind;
+use lrcshow_rs::mpd::MpdBackend;
+use lrcshow_rs::player::DbusBackend;
+
+/// Which player source to follow.
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum Backend {
+    /// MPRIS over D-Bus.
+    Dbus,
+    /// MPD's own protocol, for setups without an MPRIS bridge.
+    Mpd,
+}
+
+impl std::fmt::Display for Backend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Backend::Dbus => write!(f, "dbus"),
+            Backend::Mpd => write!(f, "mpd"),
+        }
+    }
+}
+
+/// Which filesystem-watching backend to follow the `.lrc` file's parent
+/// directory with.
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum WatcherBackend {
+    /// inotify/FSEvents/... Cheap and reacts instantly.
+    Native,
+    /// Fixed-interval scan. Slower, but works on network shares, SSHFS, and
+    /// cloud-synced folders where native backends don't fire reliably.
+    Poll,
+}
 
-static REFRESH_EVERY: Duration = Duration::from_millis(16);
+impl std::fmt::Display for WatcherBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WatcherBackend::Native => write!(f, "native"),
+            WatcherBackend::Poll => write!(f, "poll"),
+        }
+    }
+}
 
 /// Show lyrics
 #[derive(Parser, Debug)]
@@ -34,206 +58,69 @@ struct Opt {
     #[arg(short = 'l', long)]
     lyrics: Option<PathBuf>,
 
-    /// Player to use
+    /// Player to use. Pass "auto" to automatically follow whichever
+    /// MPRIS player on the session bus most recently started playing.
+    /// Ignored when `--backend mpd` is used.
     #[arg(short = 'p', long)]
     player: String,
-}
 
-struct LrcTimedTextState<'a> {
-    current: Option<&'a LyricsTiming>,
-    next: Option<&'a LyricsTiming>,
-    iter: std::slice::Iter<'a, LyricsTiming>,
-}
-
-impl<'a> LrcTimedTextState<'a> {
-    fn new(lrc: &'a Lyrics, current_position: Duration) -> LrcTimedTextState<'a> {
-        let mut iter = lrc.timings.iter();
-        let mut current = iter.next();
-        let mut next = iter.next();
-
-        while let Some(timing) = next {
-            if timing.time > current_position {
-                break;
-            }
-            current = Some(timing);
-            next = iter.next();
-        }
-        debug!(
-            "LrcTimedTextState::new; current_position = {:?}, current = {:?}",
-            current_position, current
-        );
-        LrcTimedTextState {
-            current,
-            next,
-            iter,
-        }
-    }
-
-    fn on_position_advanced(&mut self, current_position: Duration) -> Option<&'a LyricsTiming> {
-        if let Some(timed_text) = self.next {
-            let subtract = std::cmp::min(REFRESH_EVERY / 2, timed_text.time);
-            if current_position >= timed_text.time - subtract {
-                self.current = Some(timed_text);
-                self.next = self.iter.next();
-                debug!(
-                    "Matched lyrics line at time {}, player time {}",
-                    format_duration(&timed_text.time),
-                    format_duration(&current_position)
-                );
-                return Some(timed_text);
-            }
-        }
-        None
-    }
-}
-
-fn run(player: &str, lrc_filepath: &Option<PathBuf>) -> Option<()> {
-    let server = server::run_async();
-
-    let (sender, receiver) = channel::<TimedEvent>();
-
-    let player_notifs = PlayerNotifications::new(sender.clone());
-    player_notifs.run_async(player);
-
-    let lrc_manager = LrcManager::new(sender);
-    let lrc_manager_sender = lrc_manager.clone_sender();
-    if lrc_filepath.is_some() {
-        LrcManager::change_watched_path(lrc_filepath.clone(), &lrc_manager_sender);
-    }
-    lrc_manager.run_async();
-
-    let c = LocalConnection::new_session().unwrap();
-    let mut player_query: Option<QueryPlayerProperties<'_, LocalConnection>> = None;
-    let mut lrc_state: Option<LrcTimedTextState> = None;
-    let mut player_state: Option<PlayerState> = None;
-    let mut lyrics: Option<Lyrics> = None;
-
-    loop {
-        let mut received_events = false;
-        match receiver.recv_timeout(REFRESH_EVERY) {
-            Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {}
-            Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => return None,
-            Ok(timed_event) => {
-                debug!("{:?}", timed_event);
-                received_events = true;
-                let instant = timed_event.instant;
-                let event = timed_event.event;
-
-                match event {
-                    Event::PlayerEvent(PlayerEvent::Seeked { position }) => {
-                        if let Some(ref mut ps) = player_state {
-                            ps.position_snapshot = PositionSnapshot { position, instant };
-                        }
-                    }
-                    Event::PlayerEvent(PlayerEvent::PlaybackStatusChange(
-                        PlaybackStatus::Playing,
-                    )) => {
-                        // position was already queried on pause and seek
-                        player_state = player_state.map(|p| PlayerState {
-                            playback_status: PlaybackStatus::Playing,
-                            position_snapshot: PositionSnapshot {
-                                position: p.position_snapshot.position,
-                                instant,
-                            },
-                            metadata: p.metadata,
-                        });
-                    }
-                    Event::PlayerEvent(PlayerEvent::PlaybackStatusChange(
-                        PlaybackStatus::Stopped,
-                    )) => {
-                        player_state = Some(PlayerState {
-                            playback_status: PlaybackStatus::Stopped,
-                            position_snapshot: PositionSnapshot {
-                                position: Duration::from_millis(0),
-                                instant,
-                            },
-                            metadata: None,
-                        });
-                    }
-                    Event::PlayerEvent(PlayerEvent::PlaybackStatusChange(
-                        PlaybackStatus::Paused,
-                    )) => {
-                        if let (Some(p), Some(q)) = (&mut player_state, &player_query) {
-                            p.playback_status = PlaybackStatus::Paused;
-                            p.position_snapshot = PositionSnapshot {
-                                position: q.query_player_position().unwrap(),
-                                instant: Instant::now(),
-                            };
-                        }
-                    }
-                    Event::PlayerEvent(PlayerEvent::MetadataChange(metadata)) => {
-                        if lrc_filepath.is_none() {
-                            LrcManager::change_watched_path(
-                                metadata.as_ref().map(get_lrc_filepath),
-                                &lrc_manager_sender,
-                            );
-                        }
-                        if let Some(ref mut p) = player_state {
-                            p.metadata = metadata;
-                        }
-                    }
-                    Event::PlayerEvent(PlayerEvent::PlayerShutDown) => {
-                        LrcManager::change_watched_path(None, &lrc_manager_sender);
-                        player_state = None;
-                        player_query = None;
-                    }
-                    Event::PlayerEvent(PlayerEvent::PlayerStarted {
-                        player_owner_name: n,
-                    }) => {
-                        let q = QueryPlayerProperties {
-                            proxy: get_connection_proxy(&c, n),
-                        };
-                        // TODO: This is often crashing on player restart
-                        player_state = Some(q.query_player_state().unwrap());
-                        player_query = Some(q);
-
-                        if lrc_filepath.is_none() {
-                            LrcManager::change_watched_path(
-                                player_state
-                                    .as_ref()
-                                    .and_then(|p| p.metadata.as_ref().map(get_lrc_filepath)),
-                                &lrc_manager_sender,
-                            );
-                        }
-                    }
-                    Event::PlayerEvent(PlayerEvent::Unknown {
-                        key: unknown_key,
-                        value: unknown_value,
-                    }) => {
-                        warn!("Unknown player event property: {unknown_key} = {unknown_value}");
-                    }
-                    Event::LyricsEvent(LyricsEvent::LyricsChanged { lyrics: l, .. }) => {
-                        lrc_state = None; // will be asigned after event processing
-                        lyrics = l;
-                        server.on_lyrics_changed(lyrics.as_ref().map(|l| l.lines.clone()), &c);
-                    }
-                }
-
-                debug!("player_state = {:?}", player_state);
-            }
-        }
-
-        // Print new lyrics line, if needed
-        if received_events {
-            lrc_state = lyrics.as_ref().and_then(|l| {
-                player_state
-                    .as_ref()
-                    .map(|p| LrcTimedTextState::new(l, p.current_position()))
-            });
-            let timed_text = lrc_state.as_ref().and_then(|l| l.current);
-            server.on_active_lyrics_segment_changed(timed_text, &c);
-        } else if let Some(ref player_state) = player_state {
-            if player_state.playback_status == PlaybackStatus::Playing {
-                let new_timed_text = lrc_state
-                    .as_mut()
-                    .and_then(|l| l.on_position_advanced(player_state.current_position()));
-                // None also means that current lyrics segment should not change
-                if new_timed_text.is_some() {
-                    server.on_active_lyrics_segment_changed(new_timed_text, &c);
-                }
-            }
-        }
-    }
+    /// Which player source to follow.
+    #[arg(long, value_enum, default_value_t = Backend::Dbus)]
+    backend: Backend,
+
+    /// Address (`host:port`) of the MPD server. Only used with `--backend mpd`.
+    #[arg(long, default_value = "127.0.0.1:6600")]
+    mpd_addr: String,
+
+    /// Requires the `gstreamer-playback` feature. When given, also starts the
+    /// embedded GStreamer playback engine, whose D-Bus `Play`/`Pause`/`Seek`
+    /// methods drive a pipeline with sync derived from its clock. The URI
+    /// passed here is not loaded: nothing plays until `Play` is called, and
+    /// the `--backend` player is still followed alongside.
+    #[cfg(feature = "gstreamer-playback")]
+    #[arg(long)]
+    play: Option<String>,
+
+    /// Unix socket path to serve lyric-line updates on, for clients that
+    /// don't want to talk D-Bus.
+    #[arg(long, default_value = "/tmp/lrcshow.sock")]
+    ipc_socket: PathBuf,
+
+    /// Print the current lyric line as an i3bar/swaybar JSON protocol
+    /// block stream on stdout, in addition to the D-Bus and IPC outputs.
+    #[arg(long)]
+    i3bar: bool,
+
+    /// Grapheme clusters visible at once in the i3bar marquee window. Only
+    /// used with `--i3bar`.
+    #[arg(long, default_value_t = I3barConfig::default().window_width)]
+    i3bar_window_width: usize,
+
+    /// How often the i3bar marquee window advances, in milliseconds. Only
+    /// used with `--i3bar`.
+    #[arg(long, default_value_t = I3barConfig::default().tick.as_millis() as u64)]
+    i3bar_tick_ms: u64,
+
+    /// Separator printed between the end of the line and its wrapped
+    /// repeat in the i3bar marquee. Only used with `--i3bar`.
+    #[arg(long, default_value_t = I3barConfig::default().separator)]
+    i3bar_separator: String,
+
+    /// Address (`host:port`) to additionally broadcast lyrics/active-segment
+    /// updates on, as line-delimited JSON, for overlay tools (OBS, a
+    /// browser overlay) that would rather not speak D-Bus.
+    #[arg(long)]
+    listen: Option<String>,
+
+    /// Which backend to watch the `.lrc` file's parent directory with. Use
+    /// "poll" on network shares, SSHFS, or cloud-synced folders where
+    /// inotify/FSEvents don't fire reliably.
+    #[arg(long, value_enum, default_value_t = WatcherBackend::Native)]
+    watcher: WatcherBackend,
+
+    /// Poll interval in milliseconds. Only used with `--watcher poll`.
+    #[arg(long, default_value_t = 100)]
+    watcher_poll_interval_ms: u64,
 }
 
 fn main() {
@@ -249,5 +136,39 @@ fn main() {
         error!("Lyrics path must be a file");
         return;
     }
-    run(&opt.player, &lyrics_filepath);
+    let backend: Box<dyn Player> = match opt.backend {
+        Backend::Dbus => Box::new(DbusBackend::new(opt.player.clone())),
+        Backend::Mpd => Box::new(MpdBackend::new(opt.mpd_addr.clone())),
+    };
+    let watcher_kind = match opt.watcher {
+        WatcherBackend::Native => WatcherKind::Native,
+        WatcherBackend::Poll => {
+            WatcherKind::Poll(std::time::Duration::from_millis(opt.watcher_poll_interval_ms))
+        }
+    };
+    let i3bar_config = opt.i3bar.then(|| I3barConfig {
+        window_width: opt.i3bar_window_width,
+        tick: std::time::Duration::from_millis(opt.i3bar_tick_ms),
+        separator: opt.i3bar_separator,
+    });
+
+    #[cfg(feature = "gstreamer-playback")]
+    lrcshow_rs::run(
+        backend,
+        &lyrics_filepath,
+        opt.ipc_socket,
+        i3bar_config,
+        opt.listen,
+        watcher_kind,
+        opt.play.is_some(),
+    );
+    #[cfg(not(feature = "gstreamer-playback"))]
+    lrcshow_rs::run(
+        backend,
+        &lyrics_filepath,
+        opt.ipc_socket,
+        i3bar_config,
+        opt.listen,
+        watcher_kind,
+    );
 }
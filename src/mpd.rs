@@ -0,0 +1,275 @@
+//! MPD backend: an alternative to MPRIS/D-Bus for users running mpd/mopidy
+//! without an MPRIS bridge. Speaks the plain-text MPD protocol directly
+//! over TCP rather than pulling in a client crate, the same way `ipc.rs`
+//! hand-rolls its own tiny framing instead of depending on something
+//! heavier.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::path::PathBuf;
+use std::sync::mpsc::Sender;
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[allow(unused_imports)]
+use log::{debug, error, info, trace, warn};
+
+use crate::backend::{Player, PlayerQuery};
+use crate::events::{
+    Event, Metadata, MetadataLocation, PlaybackStatus, PlayerEvent, PlayerState, PositionSnapshot,
+    TimedEvent,
+};
+
+/// MPD has no per-session identity like a D-Bus unique name; every
+/// `PlayerStarted` event from this backend carries this fixed label.
+const MPD_PLAYER_OWNER_NAME: &str = "mpd";
+
+/// A persistent connection to `mpd`'s plain-text protocol. Kept open across
+/// commands rather than reconnecting per request so `idle player` (see
+/// `run_sync`) doesn't miss changes that land in the gap between one-shot
+/// connections: MPD tracks idle-worthy changes from the last command on a
+/// connection, not from when `idle` itself was issued, so only a connection
+/// that was already open when the change happened is guaranteed to see it.
+struct MpdConnection {
+    stream: TcpStream,
+    reader: BufReader<TcpStream>,
+}
+
+impl MpdConnection {
+    fn connect(addr: &str) -> Result<Self, String> {
+        let stream = TcpStream::connect(addr).map_err(|e| e.to_string())?;
+        let mut reader = BufReader::new(stream.try_clone().map_err(|e| e.to_string())?);
+
+        let mut banner = String::new();
+        reader.read_line(&mut banner).map_err(|e| e.to_string())?;
+        if !banner.starts_with("OK MPD") {
+            return Err(format!("Unexpected MPD banner: {banner}"));
+        }
+
+        Ok(MpdConnection { stream, reader })
+    }
+
+    /// A single plain-text request/response round trip. MPD replies with
+    /// zero or more `key: value` lines, terminated by `OK` (or `ACK ...` on
+    /// error).
+    fn command(&mut self, cmd: &str) -> Result<HashMap<String, String>, String> {
+        writeln!(self.stream, "{cmd}").map_err(|e| e.to_string())?;
+
+        let mut fields = HashMap::new();
+        loop {
+            let mut line = String::new();
+            let n = self.reader.read_line(&mut line).map_err(|e| e.to_string())?;
+            if n == 0 {
+                return Err("MPD closed the connection mid-response".to_owned());
+            }
+            let line = line.trim_end_matches(['\r', '\n']);
+            if line == "OK" {
+                return Ok(fields);
+            }
+            if let Some(message) = line.strip_prefix("ACK ") {
+                return Err(format!("MPD error: {message}"));
+            }
+            if let Some((key, value)) = line.split_once(": ") {
+                fields.insert(key.to_owned(), value.to_owned());
+            }
+        }
+    }
+}
+
+/// One-off command against `addr` for callers that don't keep a connection
+/// around (the `PlayerQuery` trait's on-demand position/state polling).
+fn command(addr: &str, cmd: &str) -> Result<HashMap<String, String>, String> {
+    MpdConnection::connect(addr)?.command(cmd)
+}
+
+fn parse_seconds(fields: &HashMap<String, String>, key: &str) -> Option<Duration> {
+    fields
+        .get(key)
+        .and_then(|v| v.parse::<f64>().ok())
+        .map(Duration::from_secs_f64)
+}
+
+fn parse_metadata(currentsong: &HashMap<String, String>) -> Option<Metadata> {
+    let file = currentsong.get("file")?;
+    Some(Metadata {
+        // MPD's `file` is relative to its own music directory; assuming the
+        // watcher runs on the same host/mount as the mpd instance, treating
+        // it as a path relative to the working directory is the best we can
+        // do without also reading `music_directory` out of mpd.conf.
+        location: MetadataLocation::LocalFile(PathBuf::from(file)),
+        track_id: currentsong.get("Id").cloned(),
+        title: currentsong.get("Title").cloned(),
+        artist: currentsong.get("Artist").map(|a| vec![a.clone()]),
+        album: currentsong.get("Album").cloned(),
+        length: parse_seconds(currentsong, "Time"),
+        art_url: None,
+    })
+}
+
+fn parse_playback_status(status: &HashMap<String, String>) -> PlaybackStatus {
+    match status.get("state").map(String::as_str) {
+        Some("play") => PlaybackStatus::Playing,
+        Some("pause") => PlaybackStatus::Paused,
+        _ => PlaybackStatus::Stopped,
+    }
+}
+
+/// Builds a `PlayerState` from `status`/`currentsong`, issued via
+/// `run_command` so callers can share a persistent connection (`run_sync`'s
+/// idle loop) or open one per call (`MpdQuery`'s on-demand polling).
+fn query_player_state(
+    mut run_command: impl FnMut(&str) -> Result<HashMap<String, String>, String>,
+) -> Result<PlayerState, String> {
+    let status = run_command("status")?;
+    let playback_status = parse_playback_status(&status);
+    let position = parse_seconds(&status, "elapsed").unwrap_or_default();
+    let metadata = if playback_status == PlaybackStatus::Stopped {
+        None
+    } else {
+        parse_metadata(&run_command("currentsong")?)
+    };
+    Ok(PlayerState {
+        playback_status,
+        position_snapshot: PositionSnapshot {
+            position,
+            instant: Instant::now(),
+        },
+        metadata,
+    })
+}
+
+pub struct MpdBackend {
+    addr: String,
+}
+
+impl MpdBackend {
+    pub fn new(addr: String) -> Self {
+        MpdBackend { addr }
+    }
+}
+
+impl Player for MpdBackend {
+    fn run_async(&self, sender: Sender<TimedEvent>) -> thread::JoinHandle<()> {
+        let addr = self.addr.clone();
+        thread::spawn(move || run_sync(&addr, &sender))
+    }
+
+    fn connect(&self, _player_owner_name: &str) -> Result<Box<dyn PlayerQuery>, String> {
+        Ok(Box::new(MpdQuery {
+            addr: self.addr.clone(),
+        }))
+    }
+}
+
+struct MpdQuery {
+    addr: String,
+}
+
+impl PlayerQuery for MpdQuery {
+    fn query_player_state(&self) -> Result<PlayerState, String> {
+        query_player_state(|cmd| command(&self.addr, cmd))
+    }
+
+    fn query_player_position(&self) -> Result<Duration, String> {
+        parse_seconds(&command(&self.addr, "status")?, "elapsed")
+            .ok_or_else(|| "MPD status had no elapsed field".to_owned())
+    }
+}
+
+fn send(sender: &Sender<TimedEvent>, event: PlayerEvent) {
+    sender
+        .send(TimedEvent {
+            instant: Instant::now(),
+            event: Event::PlayerEvent(event),
+        })
+        .unwrap();
+}
+
+/// Blocks on `idle player`, MPD's long-poll notification command, and
+/// translates every wakeup into the same `PlayerEvent`s the D-Bus backend
+/// produces, so `run()`'s event loop stays backend-agnostic.
+///
+/// Queries and `idle player` all run over one persistent `MpdConnection`
+/// instead of reconnecting per command: MPD only queues an idle-worthy
+/// change for connections that were already open when it happened, so
+/// reconnecting between `query_player_state` and `idle player` would let a
+/// track change or seek landing in that gap go unnoticed until some later,
+/// unrelated event woke `idle` back up.
+fn run_sync(addr: &str, sender: &Sender<TimedEvent>) {
+    let mut last_status: Option<PlaybackStatus> = None;
+    let mut last_song_id: Option<String> = None;
+    let mut last_position: Option<(Duration, Instant)> = None;
+    let mut started = false;
+    let mut conn: Option<MpdConnection> = None;
+
+    loop {
+        if conn.is_none() {
+            conn = match MpdConnection::connect(addr) {
+                Ok(c) => Some(c),
+                Err(e) => {
+                    error!("Failed to connect to MPD: {e}");
+                    thread::sleep(Duration::from_secs(1));
+                    continue;
+                }
+            };
+        }
+        let c = conn.as_mut().unwrap();
+
+        let state = match query_player_state(|cmd| c.command(cmd)) {
+            Ok(state) => state,
+            Err(e) => {
+                error!("Failed to query MPD state: {e}");
+                conn = None;
+                thread::sleep(Duration::from_secs(1));
+                continue;
+            }
+        };
+
+        if !started {
+            started = true;
+            send(
+                sender,
+                PlayerEvent::PlayerStarted {
+                    player_owner_name: MPD_PLAYER_OWNER_NAME.to_owned(),
+                },
+            );
+        }
+
+        let song_id = state.metadata.as_ref().and_then(|m| m.track_id.clone());
+        if song_id != last_song_id {
+            last_song_id = song_id;
+            last_position = None;
+            send(sender, PlayerEvent::MetadataChange(state.metadata.clone()));
+        }
+
+        if Some(state.playback_status) != last_status {
+            last_status = Some(state.playback_status);
+            send(sender, PlayerEvent::PlaybackStatusChange(state.playback_status));
+        } else if state.playback_status == PlaybackStatus::Playing {
+            // `idle player` also wakes up on seeks within the same track;
+            // tell them apart from ordinary playback by checking whether
+            // the reported position jumped further than wall-clock elapsed
+            // time could explain.
+            let position = state.position_snapshot.position;
+            if let Some((previous, seen_at)) = last_position {
+                let expected =
+                    previous + state.position_snapshot.instant.saturating_duration_since(seen_at);
+                let diff = position.as_secs_f64() - expected.as_secs_f64();
+                if diff.abs() > 1.5 {
+                    send(sender, PlayerEvent::Seeked { position });
+                }
+            }
+        }
+        last_position = Some((state.position_snapshot.position, state.position_snapshot.instant));
+
+        // `idle player` only returns once something player-related changes
+        // (play/pause/stop, seek, track change); blocking here rather than
+        // polling keeps this thread idle between actual events.
+        if let Err(e) = c.command("idle player") {
+            error!("MPD idle failed: {e}");
+            conn = None;
+            thread::sleep(Duration::from_secs(1));
+        }
+    }
+}
@@ -0,0 +1,142 @@
+//! The `--listen ADDR` broadcast sink: pushes the same lyrics/active-segment
+//! updates the D-Bus `Server` emits to every connected TCP client, as one
+//! line-delimited JSON object per update, for tools that don't want to
+//! speak D-Bus (OBS, a browser overlay) but still want word-level
+//! highlighting out of `LyricsTiming`'s char ranges.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+#[allow(unused_imports)]
+use log::{debug, error, info, warn};
+
+use serde::Serialize;
+
+use crate::lrc::LyricsTiming;
+use crate::sink::Sink;
+
+/// Bounds how long a broadcast will block on a single slow client. Without
+/// this, a client that stops reading (an overlay that loses focus, say)
+/// fills its socket buffer and `write_all` blocks forever, freezing every
+/// other sink driven from the same `run()` loop behind it.
+const WRITE_TIMEOUT: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum NetUpdate {
+    LyricsChanged {
+        lines: Option<Vec<String>>,
+    },
+    ActiveSegmentChanged {
+        line_text: Option<String>,
+        line_index: Option<i32>,
+        line_char_from_index: Option<i32>,
+        line_char_to_index: Option<i32>,
+    },
+}
+
+#[derive(Clone)]
+pub struct NetSink {
+    clients: Arc<Mutex<HashMap<usize, TcpStream>>>,
+    next_client_id: Arc<Mutex<usize>>,
+}
+
+impl NetSink {
+    /// Writes `update` to every connected client. Streams are cloned out
+    /// of `clients` and written to with the lock released, so new clients
+    /// can still be accepted meanwhile. Writes happen one after another on
+    /// the caller's thread: a slow client delays the clients after it, and
+    /// the later sinks in `run()`'s `for sink in &sinks` loop, until its
+    /// write times out (`WRITE_TIMEOUT`, set on accept).
+    fn broadcast(&self, update: &NetUpdate) {
+        let mut line = serde_json::to_vec(update).expect("NetUpdate is always serializable");
+        line.push(b'\n');
+
+        let targets: Vec<(usize, TcpStream)> = {
+            let clients = self.clients.lock().unwrap();
+            clients
+                .iter()
+                .filter_map(|(&id, stream)| stream.try_clone().ok().map(|s| (id, s)))
+                .collect()
+        };
+
+        let mut dead = Vec::new();
+        for (id, mut stream) in targets {
+            if let Err(e) = stream.write_all(&line) {
+                debug!("Dropping lyrics TCP client: {e}");
+                dead.push(id);
+            }
+        }
+
+        if !dead.is_empty() {
+            let mut clients = self.clients.lock().unwrap();
+            for id in dead {
+                clients.remove(&id);
+            }
+        }
+    }
+}
+
+impl Sink for NetSink {
+    fn lyrics_changed(&self, lines: Option<&[String]>) {
+        self.broadcast(&NetUpdate::LyricsChanged {
+            lines: lines.map(<[String]>::to_vec),
+        });
+    }
+
+    fn active_segment_changed(&self, line_text: Option<&str>, timing: Option<&LyricsTiming>) {
+        self.broadcast(&NetUpdate::ActiveSegmentChanged {
+            line_text: line_text.map(str::to_owned),
+            line_index: timing.map(|t| t.line_index),
+            line_char_from_index: timing.map(|t| t.line_char_from_index),
+            line_char_to_index: timing.map(|t| t.line_char_to_index),
+        });
+    }
+}
+
+/// Binds `addr` and starts accepting clients in the background; every
+/// accepted connection is added to the broadcast set and receives every
+/// future update as a JSON line. Past updates aren't replayed to late
+/// joiners.
+pub fn run_async(addr: String) -> (NetSink, thread::JoinHandle<()>) {
+    let sink = NetSink {
+        clients: Arc::new(Mutex::new(HashMap::new())),
+        next_client_id: Arc::new(Mutex::new(0)),
+    };
+
+    let ret = sink.clone();
+    let join_handle = thread::spawn(move || {
+        let listener = match TcpListener::bind(&addr) {
+            Ok(l) => l,
+            Err(e) => {
+                error!("Failed to bind lyrics TCP sink on {addr}: {e}");
+                return;
+            }
+        };
+        info!("Lyrics TCP sink listening on {addr}");
+
+        for stream in listener.incoming() {
+            let mut stream = match stream {
+                Ok(s) => s,
+                Err(e) => {
+                    warn!("Failed to accept lyrics TCP client: {e}");
+                    continue;
+                }
+            };
+            stream.set_write_timeout(Some(WRITE_TIMEOUT)).ok();
+            let client_id = {
+                let mut next_client_id = sink.next_client_id.lock().unwrap();
+                let id = *next_client_id;
+                *next_client_id += 1;
+                id
+            };
+            sink.clients.lock().unwrap().insert(client_id, stream);
+        }
+    });
+
+    (ret, join_handle)
+}
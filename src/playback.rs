@@ -0,0 +1,202 @@
+//! Embedded GStreamer playback engine.
+//!
+//! This is an optional subsystem (enabled via the `gstreamer-playback`
+//! feature) built on `gstreamer-rs`. It owns a `playbin` pipeline for a
+//! given audio URI and derives the playback position directly from the
+//! pipeline clock, which is monotonic and jitter-free compared to polling
+//! an external MPRIS player. This makes word/char-level lyrics highlighting
+//! accurate. `Play`/`Pause`/`Seek` are exposed on their own D-Bus object so
+//! the same daemon can both render lyrics and own playback.
+
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use dbus::blocking::SyncConnection;
+use dbus_crossroads::{Context, Crossroads};
+use gst::prelude::*;
+
+#[allow(unused_imports)]
+use log::{debug, error, info, trace, warn};
+
+use crate::events::{Event, PlayerEvent, TimedEvent};
+
+const PLAYBACK_PATH: &str = "/com/github/nikola_kocic/lrcshow_rs/Playback";
+const PLAYBACK_IFACE: &str = "com.github.nikola_kocic.lrcshow_rs.Playback";
+
+/// How often the pipeline clock is sampled to re-derive the active lyrics
+/// segment. Kept in lockstep with the player-loop `REFRESH_EVERY` in lib.rs.
+const POSITION_POLL_EVERY: Duration = Duration::from_millis(16);
+
+pub struct PlaybackEngine {
+    pipeline: gst::Element,
+}
+
+impl PlaybackEngine {
+    fn new(uri: &str) -> Result<Self, String> {
+        gst::init().map_err(|e| e.to_string())?;
+        let pipeline = gst::ElementFactory::make("playbin")
+            .property("uri", uri)
+            .build()
+            .map_err(|e| e.to_string())?;
+        Ok(PlaybackEngine { pipeline })
+    }
+
+    fn play(&self) -> Result<(), String> {
+        self.pipeline
+            .set_state(gst::State::Playing)
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    fn pause(&self) -> Result<(), String> {
+        self.pipeline
+            .set_state(gst::State::Paused)
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    fn seek(&self, position: Duration) -> Result<(), String> {
+        let clock_time = gst::ClockTime::from_useconds(position.as_micros() as u64);
+        self.pipeline
+            .seek_simple(gst::SeekFlags::FLUSH | gst::SeekFlags::KEY_UNIT, clock_time)
+            .map_err(|e| e.to_string())
+    }
+
+    fn query_position(&self) -> Option<Duration> {
+        self.pipeline
+            .query_position::<gst::ClockTime>()
+            .map(|t| Duration::from_micros(t.useconds()))
+    }
+}
+
+struct PlaybackServerData {
+    engine: Arc<Mutex<Option<PlaybackEngine>>>,
+    sender: Sender<TimedEvent>,
+}
+
+impl PlaybackServerData {
+    fn play(&mut self, uri: String) -> Result<(), String> {
+        let engine = PlaybackEngine::new(&uri)?;
+        engine.play()?;
+        *self.engine.lock().unwrap() = Some(engine);
+        self.sender
+            .send(TimedEvent {
+                instant: Instant::now(),
+                event: Event::PlayerEvent(PlayerEvent::PlaybackStatusChange(
+                    crate::events::PlaybackStatus::Playing,
+                )),
+            })
+            .unwrap();
+        Ok(())
+    }
+
+    fn pause(&mut self) -> Result<(), String> {
+        let guard = self.engine.lock().unwrap();
+        let engine = guard.as_ref().ok_or("No pipeline is loaded")?;
+        engine.pause()?;
+        self.sender
+            .send(TimedEvent {
+                instant: Instant::now(),
+                event: Event::PlayerEvent(PlayerEvent::PlaybackStatusChange(
+                    crate::events::PlaybackStatus::Paused,
+                )),
+            })
+            .unwrap();
+        Ok(())
+    }
+
+    fn seek(&mut self, position_us: i64) -> Result<(), String> {
+        let guard = self.engine.lock().unwrap();
+        let engine = guard.as_ref().ok_or("No pipeline is loaded")?;
+        let position = Duration::from_micros(u64::try_from(position_us).map_err(|e| e.to_string())?);
+        engine.seek(position)?;
+        // Re-anchor the current LyricsTiming immediately instead of waiting
+        // for the next position poll tick.
+        self.sender
+            .send(TimedEvent {
+                instant: Instant::now(),
+                event: Event::PlayerEvent(PlayerEvent::Seeked { position }),
+            })
+            .unwrap();
+        Ok(())
+    }
+}
+
+fn run_position_poll_loop(engine: Arc<Mutex<Option<PlaybackEngine>>>, sender: Sender<TimedEvent>) {
+    loop {
+        thread::sleep(POSITION_POLL_EVERY);
+        let position = {
+            let guard = engine.lock().unwrap();
+            guard.as_ref().and_then(PlaybackEngine::query_position)
+        };
+        if let Some(position) = position {
+            sender
+                .send(TimedEvent {
+                    instant: Instant::now(),
+                    event: Event::PlayerEvent(PlayerEvent::Seeked { position }),
+                })
+                .unwrap();
+        }
+    }
+}
+
+/// Starts the playback D-Bus object and its position-polling thread on its
+/// own connection (the daemon's main bus name is already owned by
+/// `server::run_async`).
+pub fn run_async(sender: Sender<TimedEvent>) {
+    let connection = Arc::new(SyncConnection::new_session().unwrap());
+    let engine: Arc<Mutex<Option<PlaybackEngine>>> = Arc::new(Mutex::new(None));
+
+    {
+        let engine = engine.clone();
+        let sender = sender.clone();
+        thread::spawn(move || run_position_poll_loop(engine, sender));
+    }
+
+    let data = PlaybackServerData { engine, sender };
+    let mut cr = Crossroads::new();
+    let iface_token = cr.register(PLAYBACK_IFACE, |b| {
+        b.method(
+            "Play",
+            ("uri",),
+            (),
+            move |_: &mut Context, data: &mut PlaybackServerData, (uri,): (String,)| {
+                data.play(uri)
+                    .map_err(|e| dbus_crossroads::MethodErr::failed(&e))
+            },
+        );
+        b.method(
+            "Pause",
+            (),
+            (),
+            move |_: &mut Context, data: &mut PlaybackServerData, ()| {
+                data.pause()
+                    .map_err(|e| dbus_crossroads::MethodErr::failed(&e))
+            },
+        );
+        b.method(
+            "Seek",
+            ("position_us",),
+            (),
+            move |_: &mut Context, data: &mut PlaybackServerData, (position_us,): (i64,)| {
+                data.seek(position_us)
+                    .map_err(|e| dbus_crossroads::MethodErr::failed(&e))
+            },
+        );
+    });
+    cr.insert(PLAYBACK_PATH, &[iface_token], data);
+
+    thread::spawn(move || {
+        use dbus::channel::MatchingReceiver;
+        let cr = Arc::new(Mutex::new(cr));
+        connection.start_receive(
+            dbus::message::MatchRule::new_method_call(),
+            Box::new(move |msg, conn| {
+                cr.lock().unwrap().handle_message(msg, conn).unwrap();
+                true
+            }),
+        );
+    });
+}
@@ -1,6 +1,8 @@
 use std::sync::{Arc, Mutex};
 use std::thread;
+use std::time::{Duration, Instant};
 
+use dbus::arg::{self, RefArg, Variant};
 use dbus::blocking::SyncConnection;
 use dbus::Message;
 use dbus_crossroads::{Context, Crossroads};
@@ -10,6 +12,53 @@ use log::{debug, error, info, warn};
 
 use crate::lrc::LyricsTiming;
 
+const LYRICS_IFACE: &str = "com.github.nikola_kocic.lrcshow_rs.Lyrics";
+const LYRICS_PATH: &str = "/com/github/nikola_kocic/lrcshow_rs/Lyrics";
+
+/// How often the rate limiter's background thread checks for a pending
+/// segment to flush once its token bucket has refilled.
+const RATE_LIMITER_TICK: Duration = Duration::from_millis(20);
+
+/// Token-bucket limiter for `ActiveLyricsSegmentChanged` emissions. Exact-state
+/// dedup (see `on_active_lyrics_segment_changed`) already drops repeats, but
+/// word/char-level timings on a busy track can still change dozens of times a
+/// second, flooding every bus subscriber. This caps how often a signal is
+/// actually sent, while always flushing the latest pending segment once the
+/// bucket refills so no client is left stale.
+struct SegmentRateLimiter {
+    max_per_second: f64,
+    tokens: f64,
+    last_refill: Instant,
+    pending: Option<Option<LyricsTiming>>,
+}
+
+impl SegmentRateLimiter {
+    fn new(max_per_second: f64) -> Self {
+        SegmentRateLimiter {
+            max_per_second,
+            tokens: max_per_second,
+            last_refill: Instant::now(),
+            pending: None,
+        }
+    }
+
+    fn refill(&mut self) {
+        let elapsed = self.last_refill.elapsed().as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.max_per_second).min(self.max_per_second);
+        self.last_refill = Instant::now();
+    }
+
+    fn try_acquire(&mut self) -> bool {
+        self.refill();
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
 #[derive(Clone)]
 struct ServerData {
     active_lyrics_lines: Arc<Mutex<Option<Vec<String>>>>,
@@ -20,6 +69,7 @@ struct ServerData {
 pub struct Server {
     connection: Arc<SyncConnection>,
     data: ServerData,
+    rate_limiter: Option<Arc<Mutex<SegmentRateLimiter>>>,
 }
 
 impl ServerData {
@@ -40,6 +90,26 @@ impl ServerData {
 }
 
 impl Server {
+    /// Emits the standard `org.freedesktop.DBus.Properties.PropertiesChanged`
+    /// signal for the `Lyrics` object, so generic status-bar widgets and
+    /// introspection tools can consume state changes without knowing about
+    /// our custom signals.
+    fn emit_properties_changed(&self, property_name: &str, value: Box<dyn RefArg>) {
+        let mut changed_properties = arg::PropMap::new();
+        changed_properties.insert(property_name.to_owned(), Variant(value));
+        let invalidated_properties: Vec<String> = Vec::new();
+
+        let s = Message::new_signal(
+            LYRICS_PATH,
+            "org.freedesktop.DBus.Properties",
+            "PropertiesChanged",
+        )
+        .unwrap()
+        .append3(LYRICS_IFACE, changed_properties, invalidated_properties);
+
+        dbus::channel::Sender::send(self.connection.as_ref(), s).unwrap();
+    }
+
     pub fn on_active_lyrics_segment_changed(&self, timing: Option<&LyricsTiming>) {
         {
             let mut prev_value = self.data.current_timing.lock().unwrap();
@@ -50,29 +120,64 @@ impl Server {
             *prev_value = timing.cloned();
         }
 
+        match &self.rate_limiter {
+            None => self.send_active_lyrics_segment_changed(timing),
+            Some(rate_limiter) => {
+                let mut rate_limiter = rate_limiter.lock().unwrap();
+                if rate_limiter.try_acquire() {
+                    rate_limiter.pending = None;
+                    self.send_active_lyrics_segment_changed(timing);
+                } else {
+                    rate_limiter.pending = Some(timing.cloned());
+                }
+            }
+        }
+    }
+
+    fn send_active_lyrics_segment_changed(&self, timing: Option<&LyricsTiming>) {
+        let current_segment = timing.map_or((-1, -1, -1), |timing| {
+            (
+                timing.line_index,
+                timing.line_char_from_index,
+                timing.line_char_to_index,
+            )
+        });
+
         let mut s = Message::new_signal(
             "/com/github/nikola_kocic/lrcshow_rs/Daemon",
             "com.github.nikola_kocic.lrcshow_rs.Daemon",
             "ActiveLyricsSegmentChanged",
         )
         .unwrap();
+        s = s.append1(current_segment);
+        dbus::channel::Sender::send(self.connection.as_ref(), s).unwrap();
 
-        if let Some(timing) = timing {
-            s = s.append1((
-                timing.line_index,
-                timing.line_char_from_index,
-                timing.line_char_to_index,
-            ));
-        } else {
-            s = s.append1((-1, -1, -1));
-        }
+        self.emit_properties_changed("CurrentSegment", Box::new(current_segment));
+    }
 
-        dbus::channel::Sender::send(self.connection.as_ref(), s).unwrap();
+    /// Periodically flushes the rate limiter's pending segment once its
+    /// token bucket has refilled, so a client isn't left stale just because
+    /// no further segment changes arrived to trigger a flush.
+    fn run_rate_limiter_flush_loop(&self, rate_limiter: Arc<Mutex<SegmentRateLimiter>>) {
+        loop {
+            thread::sleep(RATE_LIMITER_TICK);
+            let pending = {
+                let mut rate_limiter = rate_limiter.lock().unwrap();
+                if rate_limiter.pending.is_some() && rate_limiter.try_acquire() {
+                    rate_limiter.pending.take()
+                } else {
+                    None
+                }
+            };
+            if let Some(timing) = pending {
+                self.send_active_lyrics_segment_changed(timing.as_ref());
+            }
+        }
     }
 
     pub fn on_lyrics_changed(&self, lines: Option<Vec<String>>) {
         {
-            *self.data.active_lyrics_lines.lock().unwrap() = lines;
+            *self.data.active_lyrics_lines.lock().unwrap() = lines.clone();
         }
         let s = Message::new_signal(
             "/com/github/nikola_kocic/lrcshow_rs/Daemon",
@@ -84,6 +189,21 @@ impl Server {
         info!("ActiveLyricsChanged");
 
         dbus::channel::Sender::send(self.connection.as_ref(), s).unwrap();
+
+        self.emit_properties_changed("CurrentLyrics", Box::new(lines.unwrap_or_default()));
+    }
+}
+
+impl crate::sink::Sink for Server {
+    fn lyrics_changed(&self, lines: Option<&[String]>) {
+        self.on_lyrics_changed(lines.map(<[String]>::to_vec));
+    }
+
+    fn active_segment_changed(&self, _line_text: Option<&str>, timing: Option<&LyricsTiming>) {
+        // The D-Bus signal only ever carried the line/char indices, not the
+        // text itself; clients already fetch the line text separately via
+        // `GetCurrentLyrics`.
+        self.on_active_lyrics_segment_changed(timing);
     }
 }
 
@@ -94,7 +214,7 @@ fn run_dbus_server(s: Server) -> Result<Server, dbus::Error> {
     {
         let mut cr_lock = cr.lock().unwrap();
 
-        let iface_token = cr_lock.register("com.github.nikola_kocic.lrcshow_rs.Lyrics", |b| {
+        let iface_token = cr_lock.register(LYRICS_IFACE, |b| {
             b.method(
                 "GetCurrentLyrics",
                 (),
@@ -111,12 +231,15 @@ fn run_dbus_server(s: Server) -> Result<Server, dbus::Error> {
                     Ok((server.get_current_lyrics_position(),))
                 },
             );
+            // Standard org.freedesktop.DBus.Properties surface, so clients can
+            // Get/GetAll the current state atomically instead of racing the
+            // next ActiveLyrics*Changed signal.
+            b.property("CurrentLyrics")
+                .get(|_, server| Ok(server.get_current_lyrics()));
+            b.property("CurrentSegment")
+                .get(|_, server| Ok(server.get_current_lyrics_position()));
         });
-        cr_lock.insert(
-            "/com/github/nikola_kocic/lrcshow_rs/Lyrics",
-            &[iface_token],
-            s.data,
-        );
+        cr_lock.insert(LYRICS_PATH, &[iface_token], s.data);
     }
 
     use dbus::channel::MatchingReceiver;
@@ -136,14 +259,25 @@ fn run_dbus_server(s: Server) -> Result<Server, dbus::Error> {
     }
 }
 
-pub fn run_async() -> (Server, std::thread::JoinHandle<()>) {
+/// Starts the D-Bus server. `max_segment_signals_per_second`, if set, caps
+/// how often `ActiveLyricsSegmentChanged` is emitted; pass `None` to emit a
+/// signal for every distinct segment, with no throttling.
+pub fn run_async(max_segment_signals_per_second: Option<f64>) -> (Server, std::thread::JoinHandle<()>) {
     let server = Server {
         connection: Arc::new(SyncConnection::new_session().unwrap()),
         data: ServerData {
             active_lyrics_lines: Arc::new(Mutex::new(None)),
             current_timing: Arc::new(Mutex::new(None)),
         },
+        rate_limiter: max_segment_signals_per_second
+            .map(|rate| Arc::new(Mutex::new(SegmentRateLimiter::new(rate)))),
     };
+
+    if let Some(rate_limiter) = server.rate_limiter.clone() {
+        let server = server.clone();
+        thread::spawn(move || server.run_rate_limiter_flush_loop(rate_limiter));
+    }
+
     let ret = server.clone();
     let join_handle = thread::spawn(move || {
         run_dbus_server(server).unwrap();
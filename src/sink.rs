@@ -0,0 +1,13 @@
+//! Abstracts delivering "lyrics changed" / "active segment changed" updates
+//! to whatever's listening, so a new transport (the D-Bus signals in
+//! `server::Server`, the `--listen` line-delimited JSON stream in
+//! `net_sink::NetSink`, or anything added later) is one more `Sink` impl
+//! rather than another call threaded through `run()`.
+
+use crate::lrc::LyricsTiming;
+
+pub trait Sink: Send {
+    fn lyrics_changed(&self, lines: Option<&[String]>);
+
+    fn active_segment_changed(&self, line_text: Option<&str>, timing: Option<&LyricsTiming>);
+}